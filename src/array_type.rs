@@ -0,0 +1,58 @@
+use inkwell::types::{BasicType, BasicTypeEnum};
+
+use crate::array_value::ArrayValue;
+use crate::errors::{CompilationError, CompilationResult};
+use crate::types::Type;
+use crate::value::Value;
+
+type ArrayTypeIR<'ctx> = inkwell::types::ArrayType<'ctx>;
+
+#[derive(Clone, PartialEq)]
+pub struct ArrayType<'ctx> {
+    ir: ArrayTypeIR<'ctx>,
+    element_type: Box<Type<'ctx>>,
+}
+
+impl<'ctx> Into<ArrayTypeIR<'ctx>> for ArrayType<'ctx> {
+    fn into(self) -> ArrayTypeIR<'ctx> {
+        self.ir
+    }
+}
+
+impl<'ctx> Into<Type<'ctx>> for ArrayType<'ctx> {
+    fn into(self) -> Type<'ctx> {
+        Type::Array(self)
+    }
+}
+
+impl<'ctx> ArrayType<'ctx> {
+    pub fn new(element_type: Type<'ctx>, len: u32) -> CompilationResult<Self> {
+        let element_type_ir: BasicTypeEnum = element_type.clone().try_into()?;
+        Ok(ArrayType {
+            ir: element_type_ir.array_type(len),
+            element_type: Box::new(element_type),
+        })
+    }
+
+    #[inline(always)]
+    pub fn ir(&self) -> &ArrayTypeIR<'ctx> {
+        &self.ir
+    }
+
+    #[inline(always)]
+    pub fn element_type(&self) -> &Type<'ctx> {
+        self.element_type.as_ref()
+    }
+
+    #[inline(always)]
+    pub fn len(&self) -> u32 {
+        self.ir.len()
+    }
+
+    pub fn validate_value(self, value: Value<'ctx>) -> CompilationResult<ArrayValue<'ctx>> {
+        match value {
+            Value::Array(value) if value.get_type() == &self => Ok(value),
+            _ => Err(CompilationError::TypeMismatch),
+        }
+    }
+}
@@ -0,0 +1,53 @@
+use inkwell::builder::Builder;
+use inkwell::values::BasicValueEnum;
+
+use crate::array_type::ArrayType;
+use crate::errors::CompilationResult;
+use crate::value::Value;
+
+type ArrayValueIR<'ctx> = inkwell::values::ArrayValue<'ctx>;
+
+#[derive(Clone)]
+pub struct ArrayValue<'ctx> {
+    ir: ArrayValueIR<'ctx>,
+    array_type: ArrayType<'ctx>,
+}
+
+impl<'ctx> Into<Value<'ctx>> for ArrayValue<'ctx> {
+    fn into(self) -> Value<'ctx> {
+        Value::Array(self)
+    }
+}
+
+impl<'ctx> Into<ArrayValueIR<'ctx>> for ArrayValue<'ctx> {
+    fn into(self) -> ArrayValueIR<'ctx> {
+        self.ir
+    }
+}
+
+impl<'ctx> ArrayValue<'ctx> {
+    pub fn new(ir: ArrayValueIR<'ctx>, array_type: ArrayType<'ctx>) -> Self {
+        ArrayValue { ir, array_type }
+    }
+
+    pub fn get_type(&self) -> &ArrayType<'ctx> {
+        &self.array_type
+    }
+
+    pub fn set_element(
+        self,
+        builder: &Builder<'ctx>,
+        index: u32,
+        value: Value<'ctx>,
+    ) -> CompilationResult<Self> {
+        let element_type = self.array_type.element_type().clone();
+        let element_ir: BasicValueEnum = value.validate_type(builder, element_type)?.try_into()?;
+        let result_ir = builder
+            .build_insert_value(self.ir, element_ir, index, "")?
+            .into_array_value();
+        Ok(ArrayValue {
+            ir: result_ir,
+            array_type: self.array_type,
+        })
+    }
+}
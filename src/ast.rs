@@ -1,6 +1,6 @@
 pub use crate::block::Block;
-pub use crate::constant::Constant;
-pub use crate::expression::Expression;
+pub use crate::constant::{Constant, FloatConstant, IntegerConstant};
+pub use crate::expression::{BinaryOperation, Expression};
 pub use crate::statement::Statement;
 pub use crate::types::TypeSpec;
 
@@ -26,21 +26,63 @@ impl Definition {
             value: DefinitionValue::Function(function),
         }
     }
+
+    pub fn declare_extern_function(name: String, signature: FunctionSignature) -> Self {
+        Definition {
+            name,
+            value: DefinitionValue::ExternFunction(signature),
+        }
+    }
+
+    pub fn define_struct(name: String, struct_def: Struct) -> Self {
+        Definition {
+            name,
+            value: DefinitionValue::Struct(struct_def),
+        }
+    }
+
+    pub fn define_type_alias(name: String, type_spec: TypeSpec) -> Self {
+        Definition {
+            name,
+            value: DefinitionValue::TypeAlias(type_spec),
+        }
+    }
+
+    pub fn define_const(name: String, const_def: Const) -> Self {
+        Definition {
+            name,
+            value: DefinitionValue::Const(const_def),
+        }
+    }
+
+    pub fn define_global(name: String, global: Global) -> Self {
+        Definition {
+            name,
+            value: DefinitionValue::Global(global),
+        }
+    }
 }
 
 pub enum DefinitionValue {
     Function(Function),
+    ExternFunction(FunctionSignature),
+    Struct(Struct),
+    Const(Const),
+    Global(Global),
+    TypeAlias(TypeSpec),
 }
 
 #[derive(Clone)]
 pub struct FunctionSignature {
     pub args: Vec<FunctionArgument>,
-    pub return_type: TypeSpec,
+    pub is_variadic: bool,
+    pub return_type: Option<TypeSpec>,
 }
 
 #[derive(Clone)]
 pub struct FunctionArgument {
     pub name: String,
+    pub is_mutable: bool,
     pub value_type: TypeSpec,
 }
 
@@ -54,3 +96,45 @@ impl Function {
         Function { signature, body }
     }
 }
+
+pub struct Struct {
+    pub fields: Vec<StructField>,
+}
+
+impl Struct {
+    pub fn new(fields: Vec<StructField>) -> Self {
+        Struct { fields }
+    }
+}
+
+pub struct StructField {
+    pub name: String,
+    pub value_type: TypeSpec,
+}
+
+pub struct Const {
+    pub value_type: TypeSpec,
+    pub value: Box<Expression>,
+}
+
+impl Const {
+    pub fn new(value_type: TypeSpec, value: Box<Expression>) -> Self {
+        Const { value_type, value }
+    }
+}
+
+pub struct Global {
+    pub is_thread_local: bool,
+    pub value_type: TypeSpec,
+    pub value: Box<Expression>,
+}
+
+impl Global {
+    pub fn new(is_thread_local: bool, value_type: TypeSpec, value: Box<Expression>) -> Self {
+        Global {
+            is_thread_local,
+            value_type,
+            value,
+        }
+    }
+}
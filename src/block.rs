@@ -1,10 +1,23 @@
 use crate::ast::Expression;
 use crate::errors::CompilationResult;
-use crate::statement::Statement;
+use crate::statement::{
+    AssignStatement, ForStatement, IfStatement, LetStatement, Statement, WhileStatement,
+};
 
 pub trait BlockVisitor {
+    fn is_terminated(&self) -> bool;
+    fn enter_unreachable_code(&self, is_first: bool) -> CompilationResult<()>;
+    fn leave_unreachable_code(&self) -> CompilationResult<()>;
     fn enter_block(&self, block: &Block) -> CompilationResult<()>;
-    fn add_return_statement(&self, expr: &Expression) -> CompilationResult<()>;
+    fn add_expression_statement(&self, expr: &Expression) -> CompilationResult<()>;
+    fn add_let_statement(&self, stmt: &LetStatement) -> CompilationResult<()>;
+    fn add_assign_statement(&self, stmt: &AssignStatement) -> CompilationResult<()>;
+    fn add_if_statement(&self, stmt: &IfStatement) -> CompilationResult<()>;
+    fn add_while_statement(&self, stmt: &WhileStatement) -> CompilationResult<()>;
+    fn add_for_statement(&self, stmt: &ForStatement) -> CompilationResult<()>;
+    fn add_break_statement(&self) -> CompilationResult<()>;
+    fn add_continue_statement(&self) -> CompilationResult<()>;
+    fn add_return_statement(&self, expr: Option<&Expression>) -> CompilationResult<()>;
 }
 
 pub struct Block {
@@ -17,12 +30,32 @@ impl Block {
     }
 
     pub fn visit(&self, visitor: &dyn BlockVisitor) -> CompilationResult<()> {
+        let mut is_reachable = true;
         for stmt in self.statements.iter() {
+            // Statements following a `return` are unreachable. They are still translated, into
+            // a detached block, so that they are type-checked.
+            if visitor.is_terminated() {
+                visitor.enter_unreachable_code(is_reachable)?;
+                is_reachable = false;
+            }
+
             match stmt {
                 Statement::Block(block) => visitor.enter_block(block)?,
-                Statement::Return(expr) => visitor.add_return_statement(expr)?,
+                Statement::Expression(expr) => visitor.add_expression_statement(expr)?,
+                Statement::Let(stmt) => visitor.add_let_statement(stmt)?,
+                Statement::Assign(stmt) => visitor.add_assign_statement(stmt)?,
+                Statement::If(stmt) => visitor.add_if_statement(stmt)?,
+                Statement::While(stmt) => visitor.add_while_statement(stmt)?,
+                Statement::For(stmt) => visitor.add_for_statement(stmt)?,
+                Statement::Break => visitor.add_break_statement()?,
+                Statement::Continue => visitor.add_continue_statement()?,
+                Statement::Return(expr) => visitor.add_return_statement(expr.as_deref())?,
             }
         }
+
+        if !is_reachable {
+            visitor.leave_unreachable_code()?;
+        }
         Ok(())
     }
 }
@@ -1,9 +1,13 @@
 use inkwell::builder::Builder;
+use inkwell::context::Context;
+use inkwell::IntPredicate;
 
 use crate::errors::{CompilationError, CompilationResult};
 use crate::expression::{BinaryOperation, UnaryOperation};
+use crate::float_value::FloatValue;
 use crate::integer_type::IntegerType;
 use crate::integer_value::IntegerValue;
+use crate::types::{BoolTypeIR, Type};
 use crate::value::Value;
 
 type BoolValueIR<'ctx> = inkwell::values::IntValue<'ctx>;
@@ -30,6 +34,16 @@ impl<'ctx> BoolValue<'ctx> {
         BoolValue { ir }
     }
 
+    pub fn from_constant(context: &'ctx Context, value: bool) -> Self {
+        BoolValue {
+            ir: context.bool_type().const_int(value as u64, false),
+        }
+    }
+
+    pub fn get_type(&self) -> BoolTypeIR<'ctx> {
+        self.ir.get_type()
+    }
+
     pub fn to_integer(
         self,
         builder: &Builder<'ctx>,
@@ -40,6 +54,23 @@ impl<'ctx> BoolValue<'ctx> {
         Ok(IntegerValue::new(value_ir, required_type.is_signed()))
     }
 
+    pub fn cast(
+        self,
+        builder: &Builder<'ctx>,
+        target_type: &Type<'ctx>,
+    ) -> CompilationResult<Value<'ctx>> {
+        Ok(match target_type {
+            Type::Integer(target_type) => self.to_integer(builder, target_type)?.into(),
+            Type::Float(target_type) => {
+                let target_type_ir = target_type.ir().clone();
+                let result_ir = builder.build_unsigned_int_to_float(self.ir, target_type_ir, "")?;
+                FloatValue::new(result_ir).into()
+            }
+            Type::Bool(_) => self.into(),
+            _ => return Err(CompilationError::InvalidCast),
+        })
+    }
+
     pub fn binary_operation(
         self,
         builder: &Builder<'ctx>,
@@ -57,6 +88,12 @@ impl<'ctx> BoolValue<'ctx> {
             BinaryOperation::BitAnd => builder.build_and(lhs_ir, rhs_ir, "")?,
             BinaryOperation::BitXor => builder.build_xor(lhs_ir, rhs_ir, "")?,
             BinaryOperation::BitOr => builder.build_or(lhs_ir, rhs_ir, "")?,
+            BinaryOperation::Equal => {
+                builder.build_int_compare(IntPredicate::EQ, lhs_ir, rhs_ir, "")?
+            }
+            BinaryOperation::NotEqual => {
+                builder.build_int_compare(IntPredicate::NE, lhs_ir, rhs_ir, "")?
+            }
             _ => return Err(CompilationError::InvalidOperation),
         };
         Ok(Self { ir: result_ir }.into())
@@ -68,7 +105,9 @@ impl<'ctx> BoolValue<'ctx> {
         op: UnaryOperation,
     ) -> CompilationResult<Value<'ctx>> {
         let result_ir = match op {
-            UnaryOperation::BitNot => builder.build_not(self.ir, "")?,
+            UnaryOperation::BitNot | UnaryOperation::LogicalNot => {
+                builder.build_not(self.ir, "")?
+            }
             _ => return Err(CompilationError::InvalidOperation),
         };
         Ok(Self { ir: result_ir }.into())
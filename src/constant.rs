@@ -1,9 +1,393 @@
+use std::str::Chars;
+
+use crate::errors::{CompilationError, CompilationResult};
+use crate::float_type::FloatTypeWidth;
+use crate::integer_type::IntegerTypeWidth;
+
 pub enum Constant {
-    Integer(i32),
+    Integer(IntegerConstant),
+    Float(FloatConstant),
+    Bool(bool),
+    Char(char),
+    String(String),
+    Null,
 }
 
 impl Constant {
-    pub fn new_integer(value: i32) -> Self {
+    pub fn new_integer(value: IntegerConstant) -> Self {
         Constant::Integer(value)
     }
+
+    pub fn new_float(value: FloatConstant) -> Self {
+        Constant::Float(value)
+    }
+
+    pub fn new_bool(value: bool) -> Self {
+        Constant::Bool(value)
+    }
+
+    /// Parses a quoted literal such as `'\n'`.
+    pub fn parse_char(literal: &str) -> CompilationResult<Self> {
+        let value = unescape(&literal[1..literal.len() - 1])?;
+        let mut chars = value.chars();
+        match (chars.next(), chars.next()) {
+            (Some(value), None) => Ok(Constant::Char(value)),
+            _ => Err(CompilationError::InvalidCharLiteral(literal.to_string())),
+        }
+    }
+
+    /// Parses a quoted literal such as `"hello\n"`.
+    pub fn parse_string(literal: &str) -> CompilationResult<Self> {
+        Ok(Constant::String(unescape(&literal[1..literal.len() - 1])?))
+    }
+
+    pub fn new_null() -> Self {
+        Constant::Null
+    }
+}
+
+pub struct IntegerConstant {
+    pub value: u128,
+    pub suffix: Option<IntegerSuffix>,
+}
+
+#[derive(Clone)]
+pub struct IntegerSuffix {
+    pub width: IntegerTypeWidth,
+    pub is_signed: bool,
+}
+
+impl IntegerConstant {
+    /// Parses a literal such as `1_000`, `0xFFu8` or `0b1010i64`.
+    pub fn parse(literal: &str) -> CompilationResult<Self> {
+        let (digits, suffix) = match literal.find(['i', 'u']) {
+            Some(suffix_pos) => {
+                let (digits, suffix) = literal.split_at(suffix_pos);
+                (digits, Some(IntegerSuffix::parse(suffix)?))
+            }
+            None => (literal, None),
+        };
+
+        let (radix, digits) = match digits.get(..2) {
+            Some("0x") => (16, &digits[2..]),
+            Some("0o") => (8, &digits[2..]),
+            Some("0b") => (2, &digits[2..]),
+            _ => (10, digits),
+        };
+
+        let digits = digits.replace('_', "");
+        if digits.is_empty() {
+            return Err(CompilationError::InvalidIntegerLiteral(literal.to_string()));
+        }
+
+        // The lexer only accepts valid digits, so overflow is the only possible error.
+        let value = u128::from_str_radix(&digits, radix)
+            .map_err(|_| CompilationError::IntegerLiteralTooLarge(literal.to_string()))?;
+        Ok(IntegerConstant { value, suffix })
+    }
+}
+
+impl IntegerSuffix {
+    fn parse(suffix: &str) -> CompilationResult<Self> {
+        let is_signed = suffix.starts_with('i');
+        let width = match &suffix[1..] {
+            "8" => IntegerTypeWidth::I8,
+            "16" => IntegerTypeWidth::I16,
+            "32" => IntegerTypeWidth::I32,
+            "64" => IntegerTypeWidth::I64,
+            _ => return Err(CompilationError::InvalidIntegerLiteral(suffix.to_string())),
+        };
+        Ok(IntegerSuffix { width, is_signed })
+    }
+}
+
+pub struct FloatConstant {
+    pub value: f64,
+    /// The literal rounded to `f32` on its own, as rounding `value` again could differ.
+    pub f32_value: f32,
+    pub suffix: Option<FloatTypeWidth>,
+}
+
+impl FloatConstant {
+    /// Parses a literal such as `1.5`, `2e-3f32` or the hex float `0x1.8p3`.
+    pub fn parse(literal: &str) -> CompilationResult<Self> {
+        let (digits, suffix) = match literal.strip_suffix("f32") {
+            Some(digits) => (digits, Some(FloatTypeWidth::F32)),
+            None => match literal.strip_suffix("f64") {
+                Some(digits) => (digits, Some(FloatTypeWidth::F64)),
+                None => (literal, None),
+            },
+        };
+
+        let digits = digits.replace('_', "");
+        let values = match digits.strip_prefix("0x") {
+            Some(hex_digits) => {
+                Self::parse_hex(hex_digits, f64::MANTISSA_DIGITS, f64::MIN_EXP).zip(
+                    Self::parse_hex(hex_digits, f32::MANTISSA_DIGITS, f32::MIN_EXP),
+                )
+            }
+            None => digits
+                .parse::<f64>()
+                .ok()
+                .zip(digits.parse::<f32>().ok().map(f64::from)),
+        };
+        match values {
+            Some((value, f32_value)) => Ok(FloatConstant {
+                value,
+                f32_value: f32_value as f32,
+                suffix,
+            }),
+            None => Err(CompilationError::InvalidFloatLiteral(literal.to_string())),
+        }
+    }
+
+    /// The literal rounded to a float of `bit_width` bits.
+    pub fn rounded(&self, bit_width: u32) -> f64 {
+        match bit_width {
+            32 => self.f32_value as f64,
+            _ => self.value,
+        }
+    }
+
+    /// Parses `<hex mantissa>[.<hex fraction>]p<decimal exponent>`, the part after `0x`. The
+    /// result is rounded once, to nearest even, to a float with `mantissa_digits` significant
+    /// bits and `min_exp`, as in `f64::MANTISSA_DIGITS` and `f64::MIN_EXP`.
+    fn parse_hex(digits: &str, mantissa_digits: u32, min_exp: i32) -> Option<f64> {
+        let (mantissa, exponent) = digits.split_once('p')?;
+        let (integer_part, fraction_part) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+        if integer_part.is_empty() && fraction_part.is_empty() {
+            return None;
+        }
+        let mut exponent = exponent.parse::<i32>().ok()? as i64;
+
+        // Only the leading 60 to 64 bits are kept, more than either float type needs. Any
+        // nonzero digit after them only matters to break a tie, so it is kept as a sticky bit.
+        let mut significand = 0u64;
+        let mut is_inexact = false;
+        let integer_digits = integer_part.chars().map(|c| (c, false));
+        let fraction_digits = fraction_part.chars().map(|c| (c, true));
+        for (c, is_fraction) in integer_digits.chain(fraction_digits) {
+            let digit = c.to_digit(16)? as u64;
+            if significand >> 60 == 0 {
+                significand = significand << 4 | digit;
+                exponent -= if is_fraction { 4 } else { 0 };
+            } else {
+                is_inexact |= digit != 0;
+                exponent += if is_fraction { 0 } else { 4 };
+            }
+        }
+        if significand == 0 {
+            return Some(0.0);
+        }
+
+        // Subnormal results keep fewer bits, down to none at all.
+        let bit_len = (u64::BITS - significand.leading_zeros()) as i64;
+        let value_exponent = exponent + bit_len - 1;
+        let kept_bits = mantissa_digits as i64 - (min_exp as i64 - 1 - value_exponent).max(0);
+        let shift = bit_len - kept_bits;
+        if shift > u64::BITS as i64 {
+            return Some(0.0);
+        }
+        if shift > 0 {
+            let significand_bits = significand as u128;
+            let remainder = significand_bits & ((1 << shift) - 1);
+            let half = 1 << (shift - 1);
+            significand = (significand_bits >> shift) as u64;
+            if remainder > half || (remainder == half && (is_inexact || significand & 1 == 1)) {
+                significand += 1;
+            }
+            exponent += shift;
+        }
+
+        // The result is representable, so scaling by a power of two is exact. Scaling in two
+        // steps keeps the intermediate power of two from overflowing.
+        let exponent = exponent.clamp(-4096, 4096) as i32;
+        let half_exponent = exponent / 2;
+        Some(significand as f64 * 2f64.powi(half_exponent) * 2f64.powi(exponent - half_exponent))
+    }
+}
+
+fn unescape(literal: &str) -> CompilationResult<String> {
+    let mut value = String::with_capacity(literal.len());
+    let mut chars = literal.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            value.push(c);
+            continue;
+        }
+
+        let escaped = match chars.next() {
+            Some('n') => '\n',
+            Some('r') => '\r',
+            Some('t') => '\t',
+            Some('0') => '\0',
+            Some('\\') => '\\',
+            Some('\'') => '\'',
+            Some('"') => '"',
+            Some('u') => unescape_unicode(&mut chars)?,
+            Some(c) => return Err(CompilationError::InvalidEscape(format!("\\{c}"))),
+            None => return Err(CompilationError::InvalidEscape("\\".to_string())),
+        };
+        value.push(escaped);
+    }
+    Ok(value)
+}
+
+/// Parses the `{XXXX}` part of a `\u{XXXX}` escape.
+fn unescape_unicode(chars: &mut Chars) -> CompilationResult<char> {
+    let mut code_point = String::new();
+    if chars.next() == Some('{') {
+        for c in chars.by_ref() {
+            if c == '}' {
+                return u32::from_str_radix(&code_point, 16)
+                    .ok()
+                    .and_then(char::from_u32)
+                    .ok_or_else(|| {
+                        CompilationError::InvalidEscape(format!("\\u{{{code_point}}}"))
+                    });
+            }
+            code_point.push(c);
+        }
+    }
+    Err(CompilationError::InvalidEscape(format!(
+        "\\u{{{code_point}"
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_integer_literals() {
+        assert_eq!(IntegerConstant::parse("1_000").unwrap().value, 1000);
+        assert_eq!(IntegerConstant::parse("0o17").unwrap().value, 15);
+        assert_eq!(IntegerConstant::parse("0b1010").unwrap().value, 10);
+
+        let constant = IntegerConstant::parse("0xFFu8").unwrap();
+        assert_eq!(constant.value, 255);
+        let suffix = constant.suffix.unwrap();
+        assert!(suffix.width == IntegerTypeWidth::I8);
+        assert!(!suffix.is_signed);
+    }
+
+    #[test]
+    fn rejects_integer_literals_without_digits() {
+        assert!(matches!(
+            IntegerConstant::parse("0x_"),
+            Err(CompilationError::InvalidIntegerLiteral(_))
+        ));
+        assert!(matches!(
+            IntegerConstant::parse("0bi32"),
+            Err(CompilationError::InvalidIntegerLiteral(_))
+        ));
+    }
+
+    #[test]
+    fn rejects_integer_literals_overflowing_u128() {
+        assert_eq!(
+            IntegerConstant::parse("340282366920938463463374607431768211455")
+                .unwrap()
+                .value,
+            u128::MAX
+        );
+        assert!(matches!(
+            IntegerConstant::parse("340282366920938463463374607431768211456"),
+            Err(CompilationError::IntegerLiteralTooLarge(_))
+        ));
+    }
+
+    #[test]
+    fn parses_integer_suffixes() {
+        let suffix = IntegerSuffix::parse("i64").unwrap();
+        assert!(suffix.width == IntegerTypeWidth::I64);
+        assert!(suffix.is_signed);
+
+        let suffix = IntegerSuffix::parse("u16").unwrap();
+        assert!(suffix.width == IntegerTypeWidth::I16);
+        assert!(!suffix.is_signed);
+
+        assert!(IntegerSuffix::parse("i128").is_err());
+        assert!(IntegerSuffix::parse("u").is_err());
+    }
+
+    #[test]
+    fn parses_hex_floats() {
+        let constant = FloatConstant::parse("0x1.8p3f32").unwrap();
+        assert_eq!(constant.value, 12.0);
+        assert_eq!(constant.f32_value, 12.0);
+        assert!(constant.suffix == Some(FloatTypeWidth::F32));
+
+        assert_eq!(FloatConstant::parse("0xA.Cp0").unwrap().value, 10.75);
+        assert_eq!(
+            FloatConstant::parse("0x1p1023").unwrap().value,
+            2f64.powi(1023)
+        );
+        assert_eq!(
+            FloatConstant::parse("0x1.000000000000000000000000000000000001p0")
+                .unwrap()
+                .value,
+            1.0
+        );
+        assert!(FloatConstant::parse("0x1.8").is_err());
+        assert!(FloatConstant::parse("0x.p1").is_err());
+    }
+
+    #[test]
+    fn rounds_hex_floats_once() {
+        // Rounding to `f64` first would drop the last bit and leave a tie that rounds down.
+        let constant = FloatConstant::parse("0x1.000001000000001p0").unwrap();
+        assert_eq!(constant.f32_value, 1.0 + f32::EPSILON);
+
+        // Ties round to even, unless a later digit is nonzero.
+        let min_subnormal = f64::from_bits(1);
+        assert_eq!(
+            FloatConstant::parse("0x1p-1074").unwrap().value,
+            min_subnormal
+        );
+        assert_eq!(FloatConstant::parse("0x1p-1075").unwrap().value, 0.0);
+        assert_eq!(
+            FloatConstant::parse("0x1.4p-1073").unwrap().value,
+            2.0 * min_subnormal
+        );
+        assert_eq!(
+            FloatConstant::parse("0x1.40000000000000000000001p-1073")
+                .unwrap()
+                .value,
+            3.0 * min_subnormal
+        );
+        assert_eq!(
+            FloatConstant::parse("0x1.000002p-150").unwrap().f32_value,
+            f32::from_bits(1)
+        );
+        assert_eq!(FloatConstant::parse("0x1p-150").unwrap().f32_value, 0.0);
+        assert_eq!(
+            FloatConstant::parse("0x1p128").unwrap().f32_value,
+            f32::INFINITY
+        );
+    }
+
+    #[test]
+    fn unescapes_literals() {
+        assert_eq!(unescape(r#"a\tb\n\r\0\\\'\""#).unwrap(), "a\tb\n\r\0\\'\"");
+        assert_eq!(unescape(r"\u{41}\u{1F600}").unwrap(), "A\u{1F600}");
+        assert!(matches!(
+            unescape(r"\q"),
+            Err(CompilationError::InvalidEscape(_))
+        ));
+        assert!(matches!(
+            unescape("\\"),
+            Err(CompilationError::InvalidEscape(_))
+        ));
+    }
+
+    #[test]
+    fn rejects_invalid_unicode_escapes() {
+        for literal in [r"\u{D800}", r"\u{110000}", r"\u{}", r"\u{41", r"\u41"] {
+            assert!(
+                matches!(unescape(literal), Err(CompilationError::InvalidEscape(_))),
+                "{literal} should be rejected"
+            );
+        }
+    }
 }
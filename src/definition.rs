@@ -1,5 +1,13 @@
 use crate::function::Function;
+use crate::struct_type::StructType;
+use crate::types::TypeSpec;
+use crate::value::Value;
+use crate::variable::Variable;
 
 pub enum Definition<'ctx> {
     Function(Function<'ctx>),
+    Struct(StructType<'ctx>),
+    TypeAlias(TypeSpec),
+    Constant(Value<'ctx>),
+    Global(Variable<'ctx>),
 }
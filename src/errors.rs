@@ -2,6 +2,15 @@ use thiserror::Error;
 
 pub type CompilationResult<T> = Result<T, CompilationError>;
 
+#[derive(Error, Debug)]
+pub enum CompilationWarning {
+    #[error("unused result of a non-unit expression in `{0}`")]
+    UnusedResult(String),
+
+    #[error("unreachable statement in `{0}`")]
+    UnreachableCode(String),
+}
+
 #[derive(Error, Debug)]
 pub enum CompilationError {
     #[error("type mismatch")]
@@ -10,6 +19,99 @@ pub enum CompilationError {
     #[error("cannot find `{0}` in this scope")]
     UnresolvedName(String),
 
+    #[error("cannot assign to immutable `{0}`")]
+    ImmutableAssignment(String),
+
+    #[error("cannot assign to `{0}`")]
+    InvalidAssignment(String),
+
+    #[error("`{0}` outside of a loop")]
+    OutsideOfLoop(String),
+
+    #[error("cannot apply `!` to a {0} value")]
+    InvalidLogicalNot(String),
+
+    #[error("invalid cast")]
+    InvalidCast,
+
+    #[error("no field `{0}` in this struct")]
+    UnknownField(String),
+
+    #[error("missing field `{0}` in struct literal")]
+    MissingField(String),
+
+    #[error("field `{0}` specified more than once")]
+    DuplicateField(String),
+
+    #[error("index out of bounds: the len is {1} but the index is {0}")]
+    IndexOutOfBounds(i64, u32),
+
+    #[error("cannot take the address of a value that is not stored in a variable")]
+    InvalidAddressOf,
+
+    #[error("cannot assign through a `*T` pointer, a `*mut T` is required")]
+    ImmutablePointerAssignment,
+
+    #[error("cannot dereference `null`")]
+    NullDereference,
+
+    #[error("invalid integer literal `{0}`")]
+    InvalidIntegerLiteral(String),
+
+    #[error("integer literal `{0}` is too large")]
+    IntegerLiteralTooLarge(String),
+
+    #[error("invalid float literal `{0}`")]
+    InvalidFloatLiteral(String),
+
+    #[error("invalid character literal {0}")]
+    InvalidCharLiteral(String),
+
+    #[error("unknown character escape `{0}`")]
+    InvalidEscape(String),
+
+    #[error("literal out of range for `{0}`")]
+    LiteralOutOfRange(String),
+
+    #[error("attempt to divide by zero")]
+    DivisionByZero,
+
+    #[error("attempt to divide the minimum `{0}` by -1, which overflows")]
+    DivisionOverflow(String),
+
+    #[error("attempt to shift a `{0}` by at least its width")]
+    ShiftOverflow(String),
+
+    #[error("`{0}` may reach its end without returning a value")]
+    MissingReturn(String),
+
+    #[error("`{0}` is defined multiple times")]
+    DuplicateDefinition(String),
+
+    #[error("expected {0} arguments, found {1}")]
+    ArgumentCountMismatch(usize, usize),
+
+    #[error("initializer of `{0}` is not a constant expression")]
+    NonConstantInitializer(String),
+
+    #[error("type alias `{0}` refers to itself")]
+    CyclicTypeAlias(String),
+
+    #[error("initializer of constant `{0}` depends on itself")]
+    CyclicConstant(String),
+
+    #[error("struct `{0}` contains itself by value, use a pointer instead")]
+    RecursiveStruct(String),
+
+    #[error("`for` loop step must be positive")]
+    InvalidStep,
+
+    #[error("`{0}` is used by runtime checks but is declared with a different signature")]
+    ConflictingDeclaration(String),
+
+    #[error("extern procedure `{0}` passes a struct or an array by value, use a pointer instead")]
+    ExternAggregate(String),
+
     #[error("invalid operation")]
     InvalidOperation,
 
@@ -1,10 +1,20 @@
 use crate::constant::Constant;
+use crate::types::TypeSpec;
 
 pub enum Expression {
     LoadConstant(Constant),
     LoadValue(String),
     BinaryOperation(BinaryOperationExpression),
     UnaryOperation(UnaryOperationExpression),
+    LogicalOperation(LogicalOperationExpression),
+    Conditional(ConditionalExpression),
+    Cast(CastExpression),
+    StructLiteral(StructLiteralExpression),
+    FieldAccess(FieldAccessExpression),
+    ArrayLiteral(Vec<Box<Expression>>),
+    Index(IndexExpression),
+    AddressOf(Box<Expression>),
+    Dereference(Box<Expression>),
     Call(CallExpression),
 }
 
@@ -20,6 +30,26 @@ pub enum BinaryOperation {
     BitOr,
     ShiftLeft,
     ShiftRight,
+    Less,
+    Greater,
+    LessOrEqual,
+    GreaterOrEqual,
+    Equal,
+    NotEqual,
+}
+
+impl BinaryOperation {
+    pub fn is_comparison(&self) -> bool {
+        matches!(
+            self,
+            BinaryOperation::Less
+                | BinaryOperation::Greater
+                | BinaryOperation::LessOrEqual
+                | BinaryOperation::GreaterOrEqual
+                | BinaryOperation::Equal
+                | BinaryOperation::NotEqual
+        )
+    }
 }
 
 pub struct BinaryOperationExpression {
@@ -28,11 +58,24 @@ pub struct BinaryOperationExpression {
     pub rhs: Box<Expression>,
 }
 
+#[derive(Copy, Clone)]
+pub enum LogicalOperation {
+    And,
+    Or,
+}
+
+pub struct LogicalOperationExpression {
+    pub op: LogicalOperation,
+    pub lhs: Box<Expression>,
+    pub rhs: Box<Expression>,
+}
+
 #[derive(Copy, Clone)]
 pub enum UnaryOperation {
     Plus,
     Minus,
     BitNot,
+    LogicalNot,
 }
 
 pub struct UnaryOperationExpression {
@@ -40,12 +83,98 @@ pub struct UnaryOperationExpression {
     pub arg: Box<Expression>,
 }
 
+pub struct ConditionalExpression {
+    pub condition: Box<Expression>,
+    pub case_a: Box<Expression>,
+    pub case_b: Box<Expression>,
+}
+
+pub struct CastExpression {
+    pub arg: Box<Expression>,
+    pub target_type: TypeSpec,
+}
+
+pub struct StructLiteralExpression {
+    pub name: String,
+    pub fields: Vec<(String, Box<Expression>)>,
+}
+
+pub struct FieldAccessExpression {
+    pub value: Box<Expression>,
+    pub name: String,
+}
+
+pub struct IndexExpression {
+    pub value: Box<Expression>,
+    pub index: Box<Expression>,
+}
+
 pub struct CallExpression {
     pub callee: Box<Expression>,
     pub args: Vec<Box<Expression>>,
 }
 
 impl Expression {
+    /// Whether this is a numeric literal without a suffix, possibly negated.
+    pub fn is_untyped_literal(&self) -> bool {
+        match self {
+            Expression::LoadConstant(Constant::Integer(constant)) => constant.suffix.is_none(),
+            Expression::LoadConstant(Constant::Float(constant)) => constant.suffix.is_none(),
+            Expression::UnaryOperation(expr) => {
+                matches!(expr.op, UnaryOperation::Minus) && expr.arg.is_untyped_literal()
+            }
+            _ => false,
+        }
+    }
+
+    /// Collects the names of the values this expression loads, such as the constants an
+    /// initializer depends on.
+    pub fn collect_names<'a>(&'a self, names: &mut Vec<&'a str>) {
+        match self {
+            Expression::LoadConstant(_) => {}
+            Expression::LoadValue(name) => names.push(name),
+            Expression::BinaryOperation(expr) => {
+                expr.lhs.collect_names(names);
+                expr.rhs.collect_names(names);
+            }
+            Expression::UnaryOperation(expr) => expr.arg.collect_names(names),
+            Expression::LogicalOperation(expr) => {
+                expr.lhs.collect_names(names);
+                expr.rhs.collect_names(names);
+            }
+            Expression::Conditional(expr) => {
+                expr.condition.collect_names(names);
+                expr.case_a.collect_names(names);
+                expr.case_b.collect_names(names);
+            }
+            Expression::Cast(expr) => expr.arg.collect_names(names),
+            Expression::StructLiteral(expr) => {
+                for (_, field_expr) in expr.fields.iter() {
+                    field_expr.collect_names(names);
+                }
+            }
+            Expression::FieldAccess(expr) => expr.value.collect_names(names),
+            Expression::ArrayLiteral(elements) => {
+                for element in elements.iter() {
+                    element.collect_names(names);
+                }
+            }
+            Expression::Index(expr) => {
+                expr.value.collect_names(names);
+                expr.index.collect_names(names);
+            }
+            Expression::AddressOf(expr) | Expression::Dereference(expr) => {
+                expr.collect_names(names)
+            }
+            Expression::Call(expr) => {
+                expr.callee.collect_names(names);
+                for arg in expr.args.iter() {
+                    arg.collect_names(names);
+                }
+            }
+        }
+    }
+
     pub fn new_load_constant(value: Constant) -> Box<Self> {
         Box::new(Expression::LoadConstant(value))
     }
@@ -94,6 +223,30 @@ impl Expression {
         Self::new_binary_operation(BinaryOperation::ShiftRight, lhs, rhs)
     }
 
+    pub fn new_less(lhs: Box<Expression>, rhs: Box<Expression>) -> Box<Self> {
+        Self::new_binary_operation(BinaryOperation::Less, lhs, rhs)
+    }
+
+    pub fn new_greater(lhs: Box<Expression>, rhs: Box<Expression>) -> Box<Self> {
+        Self::new_binary_operation(BinaryOperation::Greater, lhs, rhs)
+    }
+
+    pub fn new_less_or_equal(lhs: Box<Expression>, rhs: Box<Expression>) -> Box<Self> {
+        Self::new_binary_operation(BinaryOperation::LessOrEqual, lhs, rhs)
+    }
+
+    pub fn new_greater_or_equal(lhs: Box<Expression>, rhs: Box<Expression>) -> Box<Self> {
+        Self::new_binary_operation(BinaryOperation::GreaterOrEqual, lhs, rhs)
+    }
+
+    pub fn new_equal(lhs: Box<Expression>, rhs: Box<Expression>) -> Box<Self> {
+        Self::new_binary_operation(BinaryOperation::Equal, lhs, rhs)
+    }
+
+    pub fn new_not_equal(lhs: Box<Expression>, rhs: Box<Expression>) -> Box<Self> {
+        Self::new_binary_operation(BinaryOperation::NotEqual, lhs, rhs)
+    }
+
     fn new_binary_operation(
         op: BinaryOperation,
         lhs: Box<Expression>,
@@ -106,6 +259,26 @@ impl Expression {
         }))
     }
 
+    pub fn new_logical_and(lhs: Box<Expression>, rhs: Box<Expression>) -> Box<Self> {
+        Self::new_logical_operation(LogicalOperation::And, lhs, rhs)
+    }
+
+    pub fn new_logical_or(lhs: Box<Expression>, rhs: Box<Expression>) -> Box<Self> {
+        Self::new_logical_operation(LogicalOperation::Or, lhs, rhs)
+    }
+
+    fn new_logical_operation(
+        op: LogicalOperation,
+        lhs: Box<Expression>,
+        rhs: Box<Expression>,
+    ) -> Box<Self> {
+        Box::new(Expression::LogicalOperation(LogicalOperationExpression {
+            op,
+            lhs,
+            rhs,
+        }))
+    }
+
     pub fn new_unary_plus(arg: Box<Expression>) -> Box<Self> {
         Self::new_unary_operation(UnaryOperation::Plus, arg)
     }
@@ -118,6 +291,10 @@ impl Expression {
         Self::new_unary_operation(UnaryOperation::BitNot, arg)
     }
 
+    pub fn new_logical_not(arg: Box<Expression>) -> Box<Self> {
+        Self::new_unary_operation(UnaryOperation::LogicalNot, arg)
+    }
+
     fn new_unary_operation(op: UnaryOperation, arg: Box<Expression>) -> Box<Self> {
         Box::new(Expression::UnaryOperation(UnaryOperationExpression {
             op,
@@ -125,6 +302,52 @@ impl Expression {
         }))
     }
 
+    pub fn new_conditional(
+        condition: Box<Expression>,
+        case_a: Box<Expression>,
+        case_b: Box<Expression>,
+    ) -> Box<Self> {
+        Box::new(Expression::Conditional(ConditionalExpression {
+            condition,
+            case_a,
+            case_b,
+        }))
+    }
+
+    pub fn new_cast(arg: Box<Expression>, target_type: TypeSpec) -> Box<Self> {
+        Box::new(Expression::Cast(CastExpression { arg, target_type }))
+    }
+
+    pub fn new_struct_literal(name: String, fields: Vec<(String, Box<Expression>)>) -> Box<Self> {
+        Box::new(Expression::StructLiteral(StructLiteralExpression {
+            name,
+            fields,
+        }))
+    }
+
+    pub fn new_field_access(value: Box<Expression>, name: String) -> Box<Self> {
+        Box::new(Expression::FieldAccess(FieldAccessExpression {
+            value,
+            name,
+        }))
+    }
+
+    pub fn new_array_literal(elements: Vec<Box<Expression>>) -> Box<Self> {
+        Box::new(Expression::ArrayLiteral(elements))
+    }
+
+    pub fn new_index(value: Box<Expression>, index: Box<Expression>) -> Box<Self> {
+        Box::new(Expression::Index(IndexExpression { value, index }))
+    }
+
+    pub fn new_address_of(arg: Box<Expression>) -> Box<Self> {
+        Box::new(Expression::AddressOf(arg))
+    }
+
+    pub fn new_dereference(arg: Box<Expression>) -> Box<Self> {
+        Box::new(Expression::Dereference(arg))
+    }
+
     pub fn new_call(callee: Box<Expression>, args: Vec<Box<Expression>>) -> Box<Self> {
         Box::new(Expression::Call(CallExpression { callee, args }))
     }
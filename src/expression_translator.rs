@@ -1,12 +1,27 @@
 use std::ops::Deref;
 
-use inkwell::values::{AnyValue, BasicValueEnum};
+use inkwell::basic_block::BasicBlock;
+use inkwell::types::BasicTypeEnum;
+use inkwell::values::{AnyValue, BasicValue, BasicValueEnum, IntValue};
 
-use crate::constant::Constant;
+use crate::array_type::ArrayType;
+use crate::array_value::ArrayValue;
+use crate::bool_value::BoolValue;
+use crate::constant::{Constant, FloatConstant, IntegerConstant};
 use crate::errors::{CompilationError, CompilationResult};
-use crate::expression::{BinaryOperation, CallExpression, Expression, UnaryOperation};
+use crate::expression::{
+    BinaryOperation, CallExpression, CastExpression, ConditionalExpression, Expression,
+    FieldAccessExpression, IndexExpression, LogicalOperation, StructLiteralExpression,
+    UnaryOperation,
+};
+use crate::float_type::FloatType;
+use crate::float_value::FloatValue;
+use crate::integer_type::IntegerType;
 use crate::integer_value::IntegerValue;
+use crate::pointer_type::PointerType;
+use crate::pointer_value::PointerValue;
 use crate::statement_translator::StatementTranslator;
+use crate::struct_value::StructValue;
 use crate::types::Type;
 use crate::value::Value;
 
@@ -36,7 +51,7 @@ impl<'ctx, 'm, 'f, 's> ExpressionTranslator<'ctx, 'm, 'f, 's> {
         expr_type: Option<&Type<'ctx>>,
     ) -> CompilationResult<Value<'ctx>> {
         let value = match expr {
-            Expression::LoadConstant(constant) => self.translate_constant(constant),
+            Expression::LoadConstant(constant) => self.translate_constant(constant, expr_type),
             Expression::LoadValue(name) => self.load_value(name),
             Expression::BinaryOperation(expr) => {
                 self.translate_binary_operation(expr.op, &expr.lhs, &expr.rhs, expr_type)
@@ -44,6 +59,20 @@ impl<'ctx, 'm, 'f, 's> ExpressionTranslator<'ctx, 'm, 'f, 's> {
             Expression::UnaryOperation(expr) => {
                 self.translate_unary_operation(expr.op, &expr.arg, expr_type)
             }
+            Expression::LogicalOperation(expr) => {
+                self.translate_logical_operation(expr.op, &expr.lhs, &expr.rhs)
+            }
+            Expression::Conditional(expr) => self.translate_conditional(expr, expr_type),
+            Expression::Cast(expr) => self.translate_cast(expr),
+            Expression::StructLiteral(expr) => self.translate_struct_literal(expr),
+            Expression::FieldAccess(expr) => self.translate_field_access(expr),
+            Expression::ArrayLiteral(elements) => self.translate_array_literal(elements, expr_type),
+            Expression::Index(expr) => self.translate_index(expr),
+            Expression::AddressOf(expr) => self.translate_address_of(expr),
+            Expression::Dereference(_) => match self.load_place(expr)? {
+                Some(variable) => variable.load(self.builder()),
+                None => Err(CompilationError::InvalidOperation),
+            },
             Expression::Call(expr) => self.translate_call(expr),
         };
 
@@ -54,15 +83,94 @@ impl<'ctx, 'm, 'f, 's> ExpressionTranslator<'ctx, 'm, 'f, 's> {
         }
     }
 
-    fn translate_constant(&self, constant: &Constant) -> CompilationResult<Value<'ctx>> {
+    pub fn translate_condition(&self, expr: &Expression) -> CompilationResult<BoolValue<'ctx>> {
+        // The condition is translated on its own and converted afterwards, so that `x & 1`
+        // tests the result of the `&` rather than each operand.
+        let bool_type = Type::new_bool(self.context());
+        let value = self.translate_expression(expr, None)?;
+        match value.validate_type(self.builder(), bool_type)? {
+            Value::Bool(value) => Ok(value),
+            _ => Err(CompilationError::TypeMismatch),
+        }
+    }
+
+    pub fn translate_integer(
+        &self,
+        expr: &Expression,
+        expr_type: Option<&IntegerType<'ctx>>,
+    ) -> CompilationResult<IntegerValue<'ctx>> {
+        let expr_type = expr_type.map(|expr_type| Type::Integer(expr_type.clone()));
+        match self.translate_expression(expr, expr_type.as_ref())? {
+            Value::Integer(value) => Ok(value),
+            _ => Err(CompilationError::TypeMismatch),
+        }
+    }
+
+    fn translate_constant(
+        &self,
+        constant: &Constant,
+        expr_type: Option<&Type<'ctx>>,
+    ) -> CompilationResult<Value<'ctx>> {
         let context = self.context();
         Ok(match constant {
-            Constant::Integer(value) => {
-                Value::Integer(IntegerValue::from_constant(context, *value))
+            Constant::Integer(value) => self.translate_integer_constant(value, false, expr_type)?,
+            Constant::Float(value) => self.translate_float_constant(value, expr_type)?.into(),
+            Constant::Bool(value) => BoolValue::from_constant(context, *value).into(),
+            Constant::Char(value) => {
+                let char_type = IntegerType::new_i32(context, false);
+                IntegerValue::from_constant(&char_type, *value as u128, false)?.into()
+            }
+            Constant::String(value) => {
+                // Strings are NUL-terminated so they can be passed straight to C functions.
+                let string_ir = self.builder().build_global_string_ptr(value, "")?;
+                let string_type = PointerType::new(context, Type::new_i8(context, false), false);
+                PointerValue::new(string_ir.as_pointer_value(), string_type).into()
             }
+            Constant::Null => PointerValue::new_null(PointerType::new_null(context)).into(),
         })
     }
 
+    fn translate_integer_constant(
+        &self,
+        constant: &IntegerConstant,
+        is_negative: bool,
+        expr_type: Option<&Type<'ctx>>,
+    ) -> CompilationResult<Value<'ctx>> {
+        let context = self.context();
+        // Unsuffixed literals take the expected integer or float type, falling back to `i32`.
+        let value_type = match (&constant.suffix, expr_type) {
+            (Some(suffix), _) => {
+                IntegerType::from_spec(context, suffix.width.clone(), suffix.is_signed)
+            }
+            (None, Some(Type::Integer(expr_type))) => expr_type.clone(),
+            (None, Some(Type::Float(expr_type))) => {
+                if !expr_type.can_represent(constant.value) {
+                    return Err(CompilationError::LiteralOutOfRange(expr_type.name()));
+                }
+                let value = constant.value as f64;
+                let value = if is_negative { -value } else { value };
+                return Ok(FloatValue::from_constant(expr_type, value)?.into());
+            }
+            (None, _) => IntegerType::new_i32(context, true),
+        };
+        Ok(IntegerValue::from_constant(&value_type, constant.value, is_negative)?.into())
+    }
+
+    fn translate_float_constant(
+        &self,
+        constant: &FloatConstant,
+        expr_type: Option<&Type<'ctx>>,
+    ) -> CompilationResult<FloatValue<'ctx>> {
+        let context = self.context();
+        // Unsuffixed literals take the expected float type, falling back to `f64`.
+        let value_type = match (&constant.suffix, expr_type) {
+            (Some(suffix), _) => FloatType::from_spec(context, suffix.clone()),
+            (None, Some(Type::Float(expr_type))) => expr_type.clone(),
+            (None, _) => FloatType::new_f64(context),
+        };
+        FloatValue::from_constant(&value_type, constant.rounded(value_type.bit_width()))
+    }
+
     fn translate_binary_operation(
         &self,
         op: BinaryOperation,
@@ -70,21 +178,389 @@ impl<'ctx, 'm, 'f, 's> ExpressionTranslator<'ctx, 'm, 'f, 's> {
         rhs_expr: &Expression,
         expr_type: Option<&Type<'ctx>>,
     ) -> CompilationResult<Value<'ctx>> {
-        let lhs = self.translate_expression(&lhs_expr, expr_type)?;
-        let rhs = self.translate_expression(&rhs_expr, expr_type)?;
+        // Comparison operands are unrelated to the boolean result type, and an expected `bool`
+        // applies to the result of an arithmetic or bitwise operator, not to its operands.
+        let operand_type = match expr_type {
+            _ if op.is_comparison() => None,
+            Some(Type::Bool(_)) => None,
+            _ => expr_type,
+        };
+        let (lhs, rhs) = self.translate_operands(lhs_expr, rhs_expr, operand_type)?;
         lhs.binary_operation(self.builder(), op, rhs)
     }
 
+    /// Translates two operands that are combined into a common type. Without an expected type,
+    /// an untyped literal takes its type from the other operand. Literals have no side effects,
+    /// so translating them last keeps evaluation order.
+    pub fn translate_operands(
+        &self,
+        lhs_expr: &Expression,
+        rhs_expr: &Expression,
+        expr_type: Option<&Type<'ctx>>,
+    ) -> CompilationResult<(Value<'ctx>, Value<'ctx>)> {
+        if expr_type.is_some() {
+            let lhs = self.translate_expression(lhs_expr, expr_type)?;
+            let rhs = self.translate_expression(rhs_expr, expr_type)?;
+            return Ok((lhs, rhs));
+        }
+
+        if lhs_expr.is_untyped_literal() {
+            let rhs = self.translate_expression(rhs_expr, None)?;
+            let lhs = self.translate_expression(lhs_expr, Self::literal_hint(&rhs).as_ref())?;
+            Ok((lhs, rhs))
+        } else {
+            let lhs = self.translate_expression(lhs_expr, None)?;
+            let rhs_type = if rhs_expr.is_untyped_literal() {
+                Self::literal_hint(&lhs)
+            } else {
+                None
+            };
+            let rhs = self.translate_expression(rhs_expr, rhs_type.as_ref())?;
+            Ok((lhs, rhs))
+        }
+    }
+
+    fn literal_hint(operand: &Value<'ctx>) -> Option<Type<'ctx>> {
+        match operand.get_type() {
+            value_type @ (Type::Integer(_) | Type::Float(_)) => Some(value_type),
+            _ => None,
+        }
+    }
+
     fn translate_unary_operation(
         &self,
         op: UnaryOperation,
         arg_expr: &Expression,
         expr_type: Option<&Type<'ctx>>,
     ) -> CompilationResult<Value<'ctx>> {
-        let arg = self.translate_expression(arg_expr, expr_type)?;
+        // `!` always yields a bool, so the expected type says nothing about the operand. An
+        // expected `bool` likewise applies to the result of `-` or `~`.
+        let arg_type = match (op, expr_type) {
+            (UnaryOperation::LogicalNot, _) | (_, Some(Type::Bool(_))) => None,
+            _ => expr_type,
+        };
+        // Negative literals are folded so that e.g. `-128i8` is in range.
+        if let (UnaryOperation::Minus, Expression::LoadConstant(Constant::Integer(constant))) =
+            (op, arg_expr)
+        {
+            return self.translate_integer_constant(constant, true, arg_type);
+        }
+
+        let arg = self.translate_expression(arg_expr, arg_type)?;
         arg.unary_operation(self.builder(), op)
     }
 
+    fn translate_logical_operation(
+        &self,
+        op: LogicalOperation,
+        lhs_expr: &Expression,
+        rhs_expr: &Expression,
+    ) -> CompilationResult<Value<'ctx>> {
+        let context = self.context();
+        let builder = self.builder();
+        let func_ir = self.function_ir().clone();
+
+        let lhs_ir: IntValue = self.translate_condition(lhs_expr)?.into();
+        let lhs_block_ir = builder.get_insert_block().unwrap();
+        let rhs_block_ir = context.append_basic_block(func_ir, "");
+        let merge_block_ir = context.append_basic_block(func_ir, "");
+        match op {
+            LogicalOperation::And => {
+                builder.build_conditional_branch(lhs_ir, rhs_block_ir, merge_block_ir)?
+            }
+            LogicalOperation::Or => {
+                builder.build_conditional_branch(lhs_ir, merge_block_ir, rhs_block_ir)?
+            }
+        };
+
+        builder.position_at_end(rhs_block_ir);
+        let rhs_ir: IntValue = self.translate_condition(rhs_expr)?.into();
+        let rhs_block_ir = builder.get_insert_block().unwrap();
+        builder.build_unconditional_branch(merge_block_ir)?;
+
+        // The edge coming straight from the left-hand side carries the value that
+        // short-circuited the expression: `false` for `&&` and `true` for `||`.
+        builder.position_at_end(merge_block_ir);
+        let bool_type_ir = context.bool_type();
+        let short_circuit_ir = match op {
+            LogicalOperation::And => bool_type_ir.const_zero(),
+            LogicalOperation::Or => bool_type_ir.const_all_ones(),
+        };
+        // A constant left-hand side decides which edge is taken, so the result is known as long
+        // as the other value is usable here. This keeps e.g. `true && false` a constant.
+        let constant_ir = match lhs_ir.get_zero_extended_constant() {
+            Some(lhs) if (lhs != 0) == matches!(op, LogicalOperation::Or) => Some(short_circuit_ir),
+            Some(_) if rhs_ir.as_instruction().is_none() => Some(rhs_ir),
+            _ => None,
+        };
+        if let Some(constant_ir) = constant_ir {
+            return Ok(BoolValue::new(constant_ir).into());
+        }
+
+        let result_ir = builder.build_phi(bool_type_ir, "")?;
+        result_ir.add_incoming(&[(&short_circuit_ir, lhs_block_ir), (&rhs_ir, rhs_block_ir)]);
+        Ok(BoolValue::new(result_ir.as_basic_value().into_int_value()).into())
+    }
+
+    fn translate_conditional(
+        &self,
+        expr: &ConditionalExpression,
+        expr_type: Option<&Type<'ctx>>,
+    ) -> CompilationResult<Value<'ctx>> {
+        let context = self.context();
+        let builder = self.builder();
+        let func_ir = self.function_ir().clone();
+
+        let condition_ir: IntValue = self.translate_condition(&expr.condition)?.into();
+        let case_a_block_ir = context.append_basic_block(func_ir, "");
+        let case_b_block_ir = context.append_basic_block(func_ir, "");
+        let merge_block_ir = context.append_basic_block(func_ir, "");
+        builder.build_conditional_branch(condition_ir, case_a_block_ir, case_b_block_ir)?;
+
+        // Without an expected type, an untyped literal arm takes its type from the other arm,
+        // as operands of binary operations do. Each arm is translated in its own block, so the
+        // order they are translated in does not matter.
+        let (case_a, case_a_block_ir, case_b, case_b_block_ir) =
+            if expr_type.is_none() && expr.case_a.is_untyped_literal() {
+                let (case_b, case_b_block_ir) =
+                    self.translate_in_block(&expr.case_b, None, case_b_block_ir)?;
+                let case_a_type = Self::literal_hint(&case_b);
+                let (case_a, case_a_block_ir) =
+                    self.translate_in_block(&expr.case_a, case_a_type.as_ref(), case_a_block_ir)?;
+                (case_a, case_a_block_ir, case_b, case_b_block_ir)
+            } else {
+                let (case_a, case_a_block_ir) =
+                    self.translate_in_block(&expr.case_a, expr_type, case_a_block_ir)?;
+                let case_b_type = match expr_type {
+                    None if expr.case_b.is_untyped_literal() => Self::literal_hint(&case_a),
+                    _ => expr_type.cloned(),
+                };
+                let (case_b, case_b_block_ir) =
+                    self.translate_in_block(&expr.case_b, case_b_type.as_ref(), case_b_block_ir)?;
+                (case_a, case_a_block_ir, case_b, case_b_block_ir)
+            };
+
+        // Both arms are left open until the common type is known, so each one can
+        // be converted in its own block before jumping to the merge point.
+        let result_type = case_a.get_type().combine_with(case_b.get_type())?;
+        builder.position_at_end(case_a_block_ir);
+        let case_a_ir = self.build_branch_with_value(case_a, &result_type, merge_block_ir)?;
+        builder.position_at_end(case_b_block_ir);
+        let case_b_ir = self.build_branch_with_value(case_b, &result_type, merge_block_ir)?;
+
+        builder.position_at_end(merge_block_ir);
+        // As with `&&` and `||`, a constant condition selects an arm without a phi.
+        let selected_ir = match condition_ir.get_zero_extended_constant() {
+            Some(0) => Some(case_b_ir),
+            Some(_) => Some(case_a_ir),
+            None => None,
+        };
+        if let Some(selected_ir) = selected_ir {
+            if selected_ir.as_instruction_value().is_none() {
+                return Value::from_ir(selected_ir.as_any_value_enum(), &result_type);
+            }
+        }
+
+        let result_type_ir: BasicTypeEnum = result_type.clone().try_into()?;
+        let result_ir = builder.build_phi(result_type_ir, "")?;
+        result_ir.add_incoming(&[(&case_a_ir, case_a_block_ir), (&case_b_ir, case_b_block_ir)]);
+        Value::from_ir(result_ir.as_any_value_enum(), &result_type)
+    }
+
+    /// Translates `expr` starting at the end of `block_ir`. Returns the value and the block
+    /// the translation ended in.
+    fn translate_in_block(
+        &self,
+        expr: &Expression,
+        expr_type: Option<&Type<'ctx>>,
+        block_ir: BasicBlock<'ctx>,
+    ) -> CompilationResult<(Value<'ctx>, BasicBlock<'ctx>)> {
+        self.builder().position_at_end(block_ir);
+        let value = self.translate_expression(expr, expr_type)?;
+        Ok((value, self.builder().get_insert_block().unwrap()))
+    }
+
+    fn build_branch_with_value(
+        &self,
+        value: Value<'ctx>,
+        value_type: &Type<'ctx>,
+        target_block_ir: BasicBlock<'ctx>,
+    ) -> CompilationResult<BasicValueEnum<'ctx>> {
+        let builder = self.builder();
+        let value_ir = value
+            .validate_type(builder, value_type.clone())?
+            .try_into()?;
+        builder.build_unconditional_branch(target_block_ir)?;
+        Ok(value_ir)
+    }
+
+    fn translate_cast(&self, expr: &CastExpression) -> CompilationResult<Value<'ctx>> {
+        let target_type = Type::from_spec(self, expr.target_type.clone())?;
+        let arg = self.translate_expression(&expr.arg, None)?;
+        arg.cast(self.builder(), self.module_ir(), &target_type)
+    }
+
+    fn translate_struct_literal(
+        &self,
+        expr: &StructLiteralExpression,
+    ) -> CompilationResult<Value<'ctx>> {
+        let struct_type = match self.load_type(&expr.name)? {
+            Type::Struct(struct_type) => struct_type,
+            _ => return Err(CompilationError::TypeMismatch),
+        };
+
+        for (name, _) in struct_type.fields().iter() {
+            if !expr.fields.iter().any(|(field_name, _)| field_name == name) {
+                return Err(CompilationError::MissingField(name.clone()));
+            }
+        }
+
+        let builder = self.builder();
+        let mut value = StructValue::new(struct_type.ir().get_undef(), struct_type.clone());
+        for (id, (name, field_expr)) in expr.fields.iter().enumerate() {
+            if expr.fields[..id]
+                .iter()
+                .any(|(other_name, _)| other_name == name)
+            {
+                return Err(CompilationError::DuplicateField(name.clone()));
+            }
+
+            let (_, field_type) = struct_type.get_field(name)?;
+            let field_value = self.translate_expression(field_expr, Some(&field_type))?;
+            value = value.set_field(builder, name, field_value)?;
+        }
+
+        Ok(value.into())
+    }
+
+    fn translate_field_access(
+        &self,
+        expr: &FieldAccessExpression,
+    ) -> CompilationResult<Value<'ctx>> {
+        match self.translate_expression(&expr.value, None)? {
+            Value::Struct(value) => value.get_field(self.builder(), &expr.name),
+            _ => Err(CompilationError::InvalidOperation),
+        }
+    }
+
+    fn translate_array_literal(
+        &self,
+        elements: &[Box<Expression>],
+        expr_type: Option<&Type<'ctx>>,
+    ) -> CompilationResult<Value<'ctx>> {
+        let element_type = match expr_type {
+            Some(Type::Array(array_type)) => Some(array_type.element_type().clone()),
+            _ => None,
+        };
+
+        // Without an expected type, untyped literal elements are translated last and take the
+        // common type of the other elements, as operands of binary operations do.
+        let mut values = Vec::with_capacity(elements.len());
+        for element_expr in elements.iter() {
+            values.push(match element_type {
+                None if element_expr.is_untyped_literal() => None,
+                _ => Some(self.translate_expression(element_expr, element_type.as_ref())?),
+            });
+        }
+        let literal_type = match &element_type {
+            Some(_) => None,
+            None => match Self::combine_types(values.iter().flatten())? {
+                Some(Type::Integer(value_type)) => Some(Type::Integer(value_type)),
+                Some(Type::Float(value_type)) => Some(Type::Float(value_type)),
+                _ => None,
+            },
+        };
+        let values = values
+            .into_iter()
+            .zip(elements.iter())
+            .map(|(value, element_expr)| match value {
+                Some(value) => Ok(value),
+                None => self.translate_expression(element_expr, literal_type.as_ref()),
+            })
+            .collect::<CompilationResult<Vec<_>>>()?;
+
+        let element_type = match element_type {
+            Some(element_type) => element_type,
+            None => Self::combine_types(values.iter())?.ok_or(CompilationError::TypeMismatch)?,
+        };
+
+        let builder = self.builder();
+        let array_type = ArrayType::new(element_type, values.len() as u32)?;
+        let mut array = ArrayValue::new(array_type.ir().get_undef(), array_type);
+        for (index, value) in values.into_iter().enumerate() {
+            array = array.set_element(builder, index as u32, value)?;
+        }
+
+        Ok(array.into())
+    }
+
+    /// Returns the common type of `values`, or `None` if there are none.
+    fn combine_types<'v>(
+        values: impl Iterator<Item = &'v Value<'ctx>>,
+    ) -> CompilationResult<Option<Type<'ctx>>>
+    where
+        'ctx: 'v,
+    {
+        let mut value_types = values.map(|value| value.get_type());
+        match value_types.next() {
+            Some(first_type) => Ok(Some(
+                value_types.try_fold(first_type, |lhs, rhs| lhs.combine_with(rhs))?,
+            )),
+            None => Ok(None),
+        }
+    }
+
+    fn translate_index(&self, expr: &IndexExpression) -> CompilationResult<Value<'ctx>> {
+        let builder = self.builder();
+        if let Some(variable) = self.load_place(&expr.value)? {
+            return self
+                .build_element_place(&variable, &expr.index)?
+                .load(builder);
+        }
+
+        // Elements can only be addressed dynamically in memory, so temporary arrays are
+        // spilled to the stack first.
+        let array = match self.translate_expression(&expr.value, None)? {
+            Value::Array(array) => array,
+            _ => return Err(CompilationError::InvalidOperation),
+        };
+        let variable = self.allocate_variable("", array.get_type().clone().into(), false)?;
+        variable.store(builder, array.into())?;
+        self.build_element_place(&variable, &expr.index)?
+            .load(builder)
+    }
+
+    fn translate_address_of(&self, arg_expr: &Expression) -> CompilationResult<Value<'ctx>> {
+        let variable = match self.load_place(arg_expr)? {
+            Some(variable) => variable,
+            None => return Err(CompilationError::InvalidAddressOf),
+        };
+
+        // Taking the address of an immutable place yields a `*T`, which cannot be assigned
+        // through.
+        let pointer_type = PointerType::new(
+            self.context(),
+            variable.get_type().clone(),
+            variable.is_mutable(),
+        );
+        Ok(PointerValue::new(variable.ir().clone(), pointer_type).into())
+    }
+
+    /// Applies the C default argument promotions to an argument passed through `...`.
+    fn promote_variadic_argument(&self, arg: Value<'ctx>) -> CompilationResult<Value<'ctx>> {
+        let context = self.context();
+        let builder = self.builder();
+        let int_type = IntegerType::new_i32(context, true);
+        Ok(match arg {
+            Value::Integer(arg) if arg.get_type().bit_width() < int_type.bit_width() => {
+                arg.resize(builder, &int_type)?.into()
+            }
+            Value::Bool(arg) => arg.to_integer(builder, &int_type)?.into(),
+            Value::Float(arg) => arg.extend(builder, &FloatType::new_f64(context))?.into(),
+            arg => arg,
+        })
+    }
+
     fn translate_call(&self, expr: &CallExpression) -> CompilationResult<Value<'ctx>> {
         let callee = match self.translate_expression(&expr.callee, None)? {
             Value::Function(callee) => callee,
@@ -94,17 +570,94 @@ impl<'ctx, 'm, 'f, 's> ExpressionTranslator<'ctx, 'm, 'f, 's> {
         let callee_type = callee.get_type();
         let callee_ir = callee.clone().into();
 
+        let arg_types = callee_type.arg_types();
+        let is_valid_count = if callee_type.is_variadic() {
+            expr.args.len() >= arg_types.len()
+        } else {
+            expr.args.len() == arg_types.len()
+        };
+        if !is_valid_count {
+            return Err(CompilationError::ArgumentCountMismatch(
+                arg_types.len(),
+                expr.args.len(),
+            ));
+        }
+
         let mut args_ir = Vec::with_capacity(expr.args.len());
-        for (arg_expr, arg_type) in expr.args.iter().zip(callee_type.arg_types().iter()) {
-            let arg_ir: BasicValueEnum = self
-                .translate_expression(arg_expr, Some(arg_type))?
-                .try_into()?;
+        for (arg_id, arg_expr) in expr.args.iter().enumerate() {
+            let arg = match arg_types.get(arg_id) {
+                Some(arg_type) => self.translate_expression(arg_expr, Some(arg_type))?,
+                None => {
+                    self.promote_variadic_argument(self.translate_expression(arg_expr, None)?)?
+                }
+            };
+            let arg_ir: BasicValueEnum = arg.try_into()?;
             args_ir.push(arg_ir.into());
         }
 
         let builder = self.builder();
         let result_ir = builder.build_call(callee_ir, args_ir.as_slice(), "")?;
         let return_type = callee_type.return_type();
+        if let Type::Unit = return_type {
+            return Ok(Value::Unit);
+        }
         Value::from_ir(result_ir.as_any_value_enum(), return_type)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::errors::CompilationError;
+    use crate::target_builder::tests::compile;
+
+    #[test]
+    fn types_integer_literals_from_context() {
+        compile(
+            "proc f(x: u8): u8 { let y: u8 = 255; let z = x + 1; return 2 * z - y; }
+             proc g(): i8 { return -128; }",
+        )
+        .unwrap();
+        for src in [
+            "proc f(): u8 { return 256; }",
+            "proc f(): i8 { return -129; }",
+            "proc f(): u32 { return -1; }",
+            "proc f(x: u8): u8 { let y = x + 256; return y; }",
+        ] {
+            assert!(
+                matches!(compile(src), Err(CompilationError::LiteralOutOfRange(_))),
+                "{src} should be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn types_integer_literals_as_floats() {
+        for float_type in ["f32", "f64"] {
+            compile(&format!(
+                "proc half(x: {float_type}): {float_type} {{ return x * 2; }}
+                 proc double(x: {float_type}): {float_type} {{ let y = x * 2; return y; }}
+                 proc zero(): {float_type} {{ let x: {float_type} = -1; return 0; }}"
+            ))
+            .unwrap();
+        }
+    }
+
+    #[test]
+    fn rejects_integer_literals_rounded_as_floats() {
+        compile("proc f(): f32 { return 16777216; }").unwrap();
+        assert!(matches!(
+            compile("proc f(): f32 { return 16777217; }"),
+            Err(CompilationError::LiteralOutOfRange(_))
+        ));
+        compile("proc f(): f64 { return 16777217; }").unwrap();
+    }
+
+    #[test]
+    fn rounds_float_literals_to_their_type() {
+        compile("const A: f32 = 0x1.000001000000001p0; const B: f32 = 16777217.0;").unwrap();
+        assert!(matches!(
+            compile("const A: f32 = 0x1p128;"),
+            Err(CompilationError::LiteralOutOfRange(_))
+        ));
+    }
+}
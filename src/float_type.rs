@@ -52,6 +52,20 @@ impl<'ctx> FloatType<'ctx> {
         self.ir.get_bit_width()
     }
 
+    /// Name of the builtin type with this width, e.g. `f32`.
+    pub fn name(&self) -> String {
+        format!("f{}", self.bit_width())
+    }
+
+    /// Whether the integer `value` converts to this type without rounding.
+    pub fn can_represent(&self, value: u128) -> bool {
+        let mantissa_digits = match self.bit_width() {
+            32 => f32::MANTISSA_DIGITS,
+            _ => f64::MANTISSA_DIGITS,
+        };
+        value == 0 || u128::BITS - value.leading_zeros() - value.trailing_zeros() <= mantissa_digits
+    }
+
     pub fn validate_value(
         &self,
         builder: &Builder<'ctx>,
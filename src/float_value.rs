@@ -1,8 +1,16 @@
 use inkwell::builder::Builder;
+use inkwell::intrinsics::Intrinsic;
+use inkwell::module::Module;
+use inkwell::values::AnyValue;
+use inkwell::FloatPredicate;
 
+use crate::bool_value::BoolValue;
 use crate::errors::{CompilationError, CompilationResult};
 use crate::expression::{BinaryOperation, UnaryOperation};
 use crate::float_type::FloatType;
+use crate::integer_type::IntegerType;
+use crate::integer_value::IntegerValue;
+use crate::types::Type;
 use crate::value::Value;
 
 type FloatValueIR<'ctx> = inkwell::values::FloatValue<'ctx>;
@@ -30,10 +38,80 @@ impl<'ctx> FloatValue<'ctx> {
         FloatValue { ir }
     }
 
+    /// Builds the constant `value`, checking that it is finite in `value_type`.
+    pub fn from_constant(value_type: &FloatType<'ctx>, value: f64) -> CompilationResult<Self> {
+        let is_in_range = match value_type.bit_width() {
+            32 => (value as f32).is_finite(),
+            _ => value.is_finite(),
+        };
+        if !is_in_range {
+            return Err(CompilationError::LiteralOutOfRange(value_type.name()));
+        }
+
+        Ok(FloatValue {
+            ir: value_type.ir().const_float(value),
+        })
+    }
+
     pub fn get_type(&self) -> FloatType<'ctx> {
         FloatType::new(self.ir.get_type())
     }
 
+    pub fn cast(
+        self,
+        builder: &Builder<'ctx>,
+        module_ir: &Module<'ctx>,
+        target_type: &Type<'ctx>,
+    ) -> CompilationResult<Value<'ctx>> {
+        Ok(match target_type {
+            Type::Integer(target_type) => self.to_integer(builder, module_ir, target_type)?.into(),
+            Type::Float(target_type) => {
+                let target_type_ir = target_type.ir().clone();
+                let result_ir = if target_type.bit_width() < self.get_type().bit_width() {
+                    builder.build_float_trunc(self.ir, target_type_ir, "")?
+                } else {
+                    builder.build_float_ext(self.ir, target_type_ir, "")?
+                };
+                Self { ir: result_ir }.into()
+            }
+            Type::Bool(_) => {
+                // Same as C: only zero is false, NaN is true.
+                let zero_ir = self.ir.get_type().const_zero();
+                let result_ir =
+                    builder.build_float_compare(FloatPredicate::UNE, self.ir, zero_ir, "")?;
+                BoolValue::new(result_ir).into()
+            }
+            _ => return Err(CompilationError::InvalidCast),
+        })
+    }
+
+    /// Rounds toward zero, saturating at the bounds of `target_type`; NaN becomes zero.
+    pub fn to_integer(
+        self,
+        builder: &Builder<'ctx>,
+        module_ir: &Module<'ctx>,
+        target_type: &IntegerType<'ctx>,
+    ) -> CompilationResult<IntegerValue<'ctx>> {
+        let intrinsic_name = if target_type.is_signed() {
+            "llvm.fptosi.sat"
+        } else {
+            "llvm.fptoui.sat"
+        };
+        let intrinsic = Intrinsic::find(intrinsic_name).unwrap();
+        let intrinsic_ir = intrinsic
+            .get_declaration(
+                module_ir,
+                &[target_type.ir().clone().into(), self.ir.get_type().into()],
+            )
+            .unwrap();
+
+        let result_ir = builder.build_call(intrinsic_ir, &[self.ir.into()], "")?;
+        Ok(IntegerValue::new(
+            result_ir.as_any_value_enum().into_int_value(),
+            target_type.is_signed(),
+        ))
+    }
+
     pub fn binary_operation(
         self,
         builder: &Builder<'ctx>,
@@ -54,6 +132,23 @@ impl<'ctx> FloatValue<'ctx> {
             BinaryOperation::Sub => builder.build_float_sub(lhs_ir, rhs_ir, "")?,
             BinaryOperation::Mul => builder.build_float_mul(lhs_ir, rhs_ir, "")?,
             BinaryOperation::Div => builder.build_float_div(lhs_ir, rhs_ir, "")?,
+            BinaryOperation::Less
+            | BinaryOperation::Greater
+            | BinaryOperation::LessOrEqual
+            | BinaryOperation::GreaterOrEqual
+            | BinaryOperation::Equal
+            | BinaryOperation::NotEqual => {
+                let predicate = match op {
+                    BinaryOperation::Less => FloatPredicate::OLT,
+                    BinaryOperation::Greater => FloatPredicate::OGT,
+                    BinaryOperation::LessOrEqual => FloatPredicate::OLE,
+                    BinaryOperation::GreaterOrEqual => FloatPredicate::OGE,
+                    BinaryOperation::Equal => FloatPredicate::OEQ,
+                    _ => FloatPredicate::ONE,
+                };
+                let result_ir = builder.build_float_compare(predicate, lhs_ir, rhs_ir, "")?;
+                return Ok(BoolValue::new(result_ir).into());
+            }
             _ => return Err(CompilationError::InvalidOperation),
         };
         Ok(Self { ir: result_ir }.into())
@@ -67,6 +162,9 @@ impl<'ctx> FloatValue<'ctx> {
         let result_ir = match op {
             UnaryOperation::Plus => self.ir.clone(),
             UnaryOperation::Minus => builder.build_float_neg(self.ir, "")?,
+            UnaryOperation::LogicalNot => {
+                return Err(CompilationError::InvalidLogicalNot("float".to_string()))
+            }
             _ => return Err(CompilationError::InvalidOperation),
         };
         Ok(Self { ir: result_ir }.into())
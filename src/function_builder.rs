@@ -2,23 +2,31 @@ use std::collections::HashMap;
 use std::ops::{Deref, DerefMut};
 
 use inkwell::builder::Builder;
-use inkwell::values::{AnyValue, FunctionValue};
+use inkwell::intrinsics::Intrinsic;
+use inkwell::types::BasicTypeEnum;
+use inkwell::values::{AnyValue, BasicMetadataValueEnum, FunctionValue, IntValue, PointerValue};
+use inkwell::{AddressSpace, IntPredicate};
 
 use crate::ast;
 use crate::block::Block;
 use crate::block::BlockVisitor;
-use crate::errors::CompilationResult;
+use crate::errors::{CompilationError, CompilationResult};
+use crate::expression::Expression;
+use crate::expression_translator::ExpressionTranslator;
 use crate::function::Function;
 use crate::module_builder::ModuleBuilder;
 use crate::statement_translator::StatementTranslator;
 use crate::types::Type;
 use crate::value::Value;
+use crate::variable::Variable;
 
 pub struct FunctionBuilder<'ctx, 'm> {
     parent: &'m mut ModuleBuilder<'ctx>,
     builder: Builder<'ctx>,
     func: Function<'ctx>,
     func_args: HashMap<String, Value<'ctx>>,
+    mutable_args: Vec<String>,
+    runtime_checks: bool,
 }
 
 impl<'ctx, 'm> Deref for FunctionBuilder<'ctx, 'm> {
@@ -44,6 +52,8 @@ impl<'ctx, 'm> FunctionBuilder<'ctx, 'm> {
         let mut func_builder = Self {
             func,
             func_args: HashMap::with_capacity(func_signature.args.len()),
+            mutable_args: Vec::new(),
+            runtime_checks: parent.runtime_checks(),
             builder: parent.context().create_builder(),
             parent,
         };
@@ -64,6 +74,9 @@ impl<'ctx, 'm> FunctionBuilder<'ctx, 'm> {
         let arg_id = self.func_args.len() as u32;
         let arg_ir = func_ir.get_nth_param(arg_id).unwrap().as_any_value_enum();
         let arg_type = Type::from_spec(self, arg_ast.value_type)?;
+        if arg_ast.is_mutable {
+            self.mutable_args.push(name.clone());
+        }
         self.func_args
             .insert(name, Value::from_ir(arg_ir, &arg_type)?);
 
@@ -78,7 +91,180 @@ impl<'ctx, 'm> FunctionBuilder<'ctx, 'm> {
         self.builder().position_at_end(body_ir);
 
         let stmt_translator = StatementTranslator::new(self);
-        stmt_translator.enter_block(&body)
+        for name in self.mutable_args.iter() {
+            let value = self.func_args[name].clone();
+            let variable = self.allocate_variable(name, value.get_type(), true)?;
+            variable.store(self.builder(), value)?;
+            stmt_translator.add_variable(name.clone(), variable);
+        }
+
+        stmt_translator.enter_block(&body)?;
+
+        // Control falling off the end of the body is an implicit `return;`. A block other than
+        // the entry without predecessors, such as the exit of `while true`, is never reached.
+        if !self.is_terminated() {
+            let block_ir = self.builder().get_insert_block().unwrap();
+            let is_reachable = block_ir.get_first_use().is_some() || block_ir == body_ir;
+            match self.function_return_type() {
+                Type::Unit => self.builder().build_return(None)?,
+                _ if !is_reachable => self.builder().build_unreachable()?,
+                _ => return Err(CompilationError::MissingReturn(self.function_name())),
+            };
+        }
+        Ok(())
+    }
+
+    /// Enables or disables runtime checks in this procedure only.
+    pub fn set_runtime_checks(&mut self, enabled: bool) {
+        self.runtime_checks = enabled;
+    }
+
+    #[inline(always)]
+    pub fn runtime_checks(&self) -> bool {
+        self.runtime_checks
+    }
+
+    /// Translates the initializer of a module-level constant or global.
+    pub fn translate_initializer(
+        &self,
+        expr: &Expression,
+        value_type: &Type<'ctx>,
+    ) -> CompilationResult<Value<'ctx>> {
+        let body_ir = self
+            .context()
+            .append_basic_block(self.function_ir().clone(), "");
+        self.builder().position_at_end(body_ir);
+
+        let stmt_translator = StatementTranslator::new(self);
+        ExpressionTranslator::new(&stmt_translator).translate_expression(expr, Some(value_type))
+    }
+
+    pub fn allocate_variable(
+        &self,
+        name: &str,
+        value_type: Type<'ctx>,
+        is_mutable: bool,
+    ) -> CompilationResult<Variable<'ctx>> {
+        // Allocas are kept in the entry block so LLVM can promote them to registers.
+        let entry_block_ir = self.function_ir().get_first_basic_block().unwrap();
+        let alloca_builder = self.context().create_builder();
+        match entry_block_ir.get_first_instruction() {
+            Some(instruction_ir) => alloca_builder.position_before(&instruction_ir),
+            None => alloca_builder.position_at_end(entry_block_ir),
+        }
+
+        let value_type_ir: BasicTypeEnum = value_type.clone().try_into()?;
+        let variable_ir = alloca_builder.build_alloca(value_type_ir, name)?;
+        Ok(Variable::new(variable_ir, value_type, is_mutable))
+    }
+
+    /// Traps unless `index_ir` (an i64) is below `len`. Does nothing when runtime checks
+    /// are disabled.
+    pub fn build_bounds_check(&self, index_ir: IntValue<'ctx>, len: u32) -> CompilationResult<()> {
+        if !self.runtime_checks() {
+            return Ok(());
+        }
+
+        let len_ir = self.context().i64_type().const_int(len as u64, false);
+        let is_in_bounds_ir =
+            self.builder()
+                .build_int_compare(IntPredicate::ULT, index_ir, len_ir, "")?;
+        self.build_runtime_check(
+            is_in_bounds_ir,
+            "index out of bounds: the len is %llu but the index is %lld\n",
+            &[len_ir.into(), index_ir.into()],
+        )
+    }
+
+    /// Traps if `pointer_ir` is null. Does nothing when runtime checks are disabled.
+    pub fn build_null_check(&self, pointer_ir: PointerValue<'ctx>) -> CompilationResult<()> {
+        if !self.runtime_checks() {
+            return Ok(());
+        }
+
+        let is_not_null_ir = self.builder().build_is_not_null(pointer_ir, "")?;
+        self.build_runtime_check(is_not_null_ir, "null pointer dereference\n", &[])
+    }
+
+    /// Traps unless the `for` loop step `step_ir` is positive. Does nothing when runtime
+    /// checks are disabled.
+    pub fn build_step_check(
+        &self,
+        step_ir: IntValue<'ctx>,
+        is_signed: bool,
+    ) -> CompilationResult<()> {
+        if !self.runtime_checks() {
+            return Ok(());
+        }
+
+        let predicate = if is_signed {
+            IntPredicate::SGT
+        } else {
+            IntPredicate::NE
+        };
+        let zero_ir = step_ir.get_type().const_zero();
+        let is_positive_ir = self
+            .builder()
+            .build_int_compare(predicate, step_ir, zero_ir, "")?;
+        self.build_runtime_check(is_positive_ir, "for loop step must be positive\n", &[])
+    }
+
+    fn build_runtime_check(
+        &self,
+        is_valid_ir: IntValue<'ctx>,
+        message: &str,
+        args: &[BasicMetadataValueEnum<'ctx>],
+    ) -> CompilationResult<()> {
+        let context = self.context();
+        let builder = self.builder();
+        let func_ir = self.function_ir().clone();
+        let failure_block_ir = context.append_basic_block(func_ir, "");
+        let success_block_ir = context.append_basic_block(func_ir, "");
+        builder.build_conditional_branch(is_valid_ir, success_block_ir, failure_block_ir)?;
+
+        builder.position_at_end(failure_block_ir);
+        self.build_trap(message, args)?;
+
+        builder.position_at_end(success_block_ir);
+        Ok(())
+    }
+
+    /// Prints a `dprintf`-formatted diagnostic to stderr and aborts execution.
+    fn build_trap(
+        &self,
+        message: &str,
+        args: &[BasicMetadataValueEnum<'ctx>],
+    ) -> CompilationResult<()> {
+        let context = self.context();
+        let builder = self.builder();
+
+        let i32_type_ir = context.i32_type();
+        let ptr_type_ir = context.ptr_type(AddressSpace::default());
+        let dprintf_type_ir = i32_type_ir.fn_type(&[i32_type_ir.into(), ptr_type_ir.into()], true);
+        let dprintf_ir = self.get_or_declare_function("dprintf", dprintf_type_ir)?;
+
+        let message_ir = builder.build_global_string_ptr(message, "")?;
+        let mut dprintf_args = vec![
+            i32_type_ir.const_int(2, false).into(),
+            message_ir.as_pointer_value().into(),
+        ];
+        dprintf_args.extend_from_slice(args);
+        builder.build_call(dprintf_ir, &dprintf_args, "")?;
+
+        let trap_ir = Intrinsic::find("llvm.trap")
+            .unwrap()
+            .get_declaration(self.module_ir(), &[])
+            .unwrap();
+        builder.build_call(trap_ir, &[], "")?;
+        builder.build_unreachable()?;
+        Ok(())
+    }
+
+    pub fn is_terminated(&self) -> bool {
+        match self.builder.get_insert_block() {
+            Some(block_ir) => block_ir.get_terminator().is_some(),
+            None => false,
+        }
     }
 
     #[inline(always)]
@@ -91,19 +277,84 @@ impl<'ctx, 'm> FunctionBuilder<'ctx, 'm> {
         self.func.get_return_type()
     }
 
+    pub fn function_name(&self) -> String {
+        self.function_ir().get_name().to_string_lossy().into_owned()
+    }
+
     #[inline(always)]
     pub fn function_ir(&self) -> &FunctionValue<'ctx> {
         self.func.ir()
     }
 
+    /// Resolves a module-level `var`, unless an argument shadows it.
+    pub fn load_global(&self, name: &str) -> Option<Variable<'ctx>> {
+        if self.func_args.contains_key(name) {
+            None
+        } else {
+            self.parent.load_global(name)
+        }
+    }
+
     pub fn load_value(&self, name: &str) -> CompilationResult<Value<'ctx>> {
         match self.func_args.get(name) {
             Some(arg) => Ok(arg.clone()),
-            None => self.parent.load_value(name),
+            None => self.parent.load_value(self.builder(), name),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::errors::CompilationError;
+    use crate::target_builder::tests::compile;
+
+    #[test]
+    fn accepts_unreachable_end_of_body() {
+        compile("proc f(): i64 { while true { return 3; } }").unwrap();
+        compile("proc f(x: i64): i64 { while true { if x > 0 { return x; } } }").unwrap();
+        compile("proc f(): i64 { while false { return 3; } return 4; }").unwrap();
+    }
+
+    #[test]
+    fn rejects_reachable_end_of_body() {
+        for src in [
+            "proc f(): i64 { }",
+            "proc f(): i64 { while true { break; } }",
+            "proc f(x: bool): i64 { while x { return 3; } }",
+        ] {
+            assert!(
+                matches!(compile(src), Err(CompilationError::MissingReturn(_))),
+                "{src} should be rejected"
+            );
         }
     }
 
-    pub fn build(self) -> Function<'ctx> {
-        self.func
+    #[test]
+    fn checks_array_indices() {
+        compile(
+            "proc f(a: [i64; 3], i: i64): i64 { return a[i]; }
+             proc g(p: *i64): i64 { return *p; }",
+        )
+        .unwrap();
+        assert!(matches!(
+            compile("proc f(a: [i64; 3]): i64 { return a[3]; }"),
+            Err(CompilationError::IndexOutOfBounds(3, 3))
+        ));
+    }
+
+    #[test]
+    fn shares_dprintf_with_runtime_checks() {
+        compile(
+            "extern proc dprintf(fd: i32, format: *u8, ...): i32;
+             proc f(a: [i64; 3], i: i64): i64 { return a[i]; }",
+        )
+        .unwrap();
+        assert!(matches!(
+            compile(
+                "extern proc dprintf(fd: i64): i64;
+                 proc f(a: [i64; 3], i: i64): i64 { return a[i]; }"
+            ),
+            Err(CompilationError::ConflictingDeclaration(_))
+        ));
     }
 }
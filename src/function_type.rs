@@ -13,6 +13,7 @@ type FunctionTypeIR<'ctx> = inkwell::types::FunctionType<'ctx>;
 pub struct FunctionType<'ctx> {
     ir: FunctionTypeIR<'ctx>,
     arg_types: Vec<Type<'ctx>>,
+    is_variadic: bool,
     return_type: Box<Type<'ctx>>,
 }
 
@@ -37,14 +38,29 @@ impl<'ctx> FunctionType<'ctx> {
             arg_types_ir.push(arg_type_ir.into());
         }
 
-        let return_type = Type::from_spec(module_builder, signature.return_type.clone())?;
-        let return_type_ir: BasicTypeEnum = return_type.clone().try_into()?;
-        let func_type_ir = return_type_ir.fn_type(&arg_types_ir, false);
+        let (return_type, func_type_ir) = match &signature.return_type {
+            Some(return_type) => {
+                let return_type = Type::from_spec(module_builder, return_type.clone())?;
+                let return_type_ir: BasicTypeEnum = return_type.clone().try_into()?;
+                (
+                    return_type,
+                    return_type_ir.fn_type(&arg_types_ir, signature.is_variadic),
+                )
+            }
+            None => {
+                let void_type_ir = module_builder.context().void_type();
+                (
+                    Type::new_unit(),
+                    void_type_ir.fn_type(&arg_types_ir, signature.is_variadic),
+                )
+            }
+        };
 
         Ok(FunctionType {
             ir: func_type_ir,
             return_type: Box::new(return_type),
             arg_types,
+            is_variadic: signature.is_variadic,
         })
     }
 
@@ -53,11 +69,25 @@ impl<'ctx> FunctionType<'ctx> {
         &self.arg_types
     }
 
+    #[inline(always)]
+    pub fn is_variadic(&self) -> bool {
+        self.is_variadic
+    }
+
     #[inline(always)]
     pub fn return_type(&self) -> &Type<'ctx> {
         self.return_type.as_ref()
     }
 
+    /// Whether a struct or an array is passed or returned by value. LLVM passes these as
+    /// first-class aggregates, which does not follow the C calling convention.
+    pub fn has_aggregates(&self) -> bool {
+        self.arg_types
+            .iter()
+            .chain([self.return_type()])
+            .any(|value_type| matches!(value_type, Type::Struct(_) | Type::Array(_)))
+    }
+
     #[inline(always)]
     pub fn ir(&self) -> &FunctionTypeIR<'ctx> {
         &self.ir
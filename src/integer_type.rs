@@ -70,6 +70,23 @@ impl<'ctx> IntegerType<'ctx> {
         self.ir.get_bit_width()
     }
 
+    /// Name of the builtin type with this width and signedness, e.g. `u8`.
+    pub fn name(&self) -> String {
+        let prefix = if self.is_signed { "i" } else { "u" };
+        format!("{}{}", prefix, self.bit_width())
+    }
+
+    pub fn can_represent(&self, value: u128, is_negative: bool) -> bool {
+        let bit_width = self.bit_width();
+        if is_negative {
+            value == 0 || (self.is_signed && value <= 1 << (bit_width - 1))
+        } else if self.is_signed {
+            value < 1 << (bit_width - 1)
+        } else {
+            value < 1 << bit_width
+        }
+    }
+
     pub fn is_compatible(&self, other_type: &IntegerType<'ctx>) -> bool {
         if self.is_signed == other_type.is_signed {
             self.bit_width() <= other_type.bit_width()
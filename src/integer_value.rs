@@ -1,5 +1,4 @@
 use inkwell::builder::Builder;
-use inkwell::context::Context;
 use inkwell::IntPredicate;
 
 use crate::bool_value::BoolValue;
@@ -8,6 +7,7 @@ use crate::expression::{BinaryOperation, UnaryOperation};
 use crate::float_type::FloatType;
 use crate::float_value::FloatValue;
 use crate::integer_type::IntegerType;
+use crate::types::Type;
 use crate::value::Value;
 
 type IntegerValueIR<'ctx> = inkwell::values::IntValue<'ctx>;
@@ -35,11 +35,25 @@ impl<'ctx> IntegerValue<'ctx> {
         IntegerValue { ir, is_signed }
     }
 
-    pub fn from_constant(context: &'ctx Context, value: i32) -> Self {
-        IntegerValue {
-            ir: context.i32_type().const_int(value as u64, true),
-            is_signed: true,
+    /// Builds the constant `value`, negated if `is_negative`, checking that it fits `value_type`.
+    pub fn from_constant(
+        value_type: &IntegerType<'ctx>,
+        value: u128,
+        is_negative: bool,
+    ) -> CompilationResult<Self> {
+        if !value_type.can_represent(value, is_negative) {
+            return Err(CompilationError::LiteralOutOfRange(value_type.name()));
         }
+
+        let bits = if is_negative {
+            (value as u64).wrapping_neg()
+        } else {
+            value as u64
+        };
+        Ok(IntegerValue {
+            ir: value_type.ir().const_int(bits, is_negative),
+            is_signed: value_type.is_signed(),
+        })
     }
 
     pub fn get_type(&self) -> IntegerType<'ctx> {
@@ -81,6 +95,40 @@ impl<'ctx> IntegerValue<'ctx> {
         Ok(FloatValue::new(result_ir))
     }
 
+    /// The builder folds constant operands, and these operations would fold into poison.
+    fn check_constant_operands(
+        op: BinaryOperation,
+        lhs_ir: IntegerValueIR<'ctx>,
+        rhs_ir: IntegerValueIR<'ctx>,
+        value_type: &IntegerType<'ctx>,
+    ) -> CompilationResult<()> {
+        let bit_width = value_type.bit_width();
+        match op {
+            BinaryOperation::Div | BinaryOperation::Mod => {
+                if rhs_ir.get_zero_extended_constant() == Some(0) {
+                    return Err(CompilationError::DivisionByZero);
+                }
+                let min = i64::MIN >> (64 - bit_width);
+                if value_type.is_signed()
+                    && rhs_ir.get_sign_extended_constant() == Some(-1)
+                    && lhs_ir.get_sign_extended_constant() == Some(min)
+                {
+                    return Err(CompilationError::DivisionOverflow(value_type.name()));
+                }
+            }
+            BinaryOperation::ShiftLeft | BinaryOperation::ShiftRight => {
+                match rhs_ir.get_zero_extended_constant() {
+                    Some(shift) if shift >= bit_width as u64 => {
+                        return Err(CompilationError::ShiftOverflow(value_type.name()))
+                    }
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
     pub fn binary_operation(
         self,
         builder: &Builder<'ctx>,
@@ -92,10 +140,11 @@ impl<'ctx> IntegerValue<'ctx> {
             _ => return Err(CompilationError::TypeMismatch),
         };
 
-        let is_signed = self.is_signed;
         let result_type = self.get_type().combine_with(other.get_type())?;
+        let is_signed = result_type.is_signed();
         let lhs_ir = self.extend(builder, &result_type)?.ir;
         let rhs_ir = other.extend(builder, &result_type)?.ir;
+        Self::check_constant_operands(op, lhs_ir, rhs_ir, &result_type)?;
         let result_ir = match op {
             BinaryOperation::Add => builder.build_int_add(lhs_ir, rhs_ir, ""),
             BinaryOperation::Sub => builder.build_int_sub(lhs_ir, rhs_ir, ""),
@@ -119,6 +168,16 @@ impl<'ctx> IntegerValue<'ctx> {
             BinaryOperation::BitOr => builder.build_or(lhs_ir, rhs_ir, ""),
             BinaryOperation::ShiftLeft => builder.build_left_shift(lhs_ir, rhs_ir, ""),
             BinaryOperation::ShiftRight => builder.build_right_shift(lhs_ir, rhs_ir, is_signed, ""),
+            BinaryOperation::Less
+            | BinaryOperation::Greater
+            | BinaryOperation::LessOrEqual
+            | BinaryOperation::GreaterOrEqual
+            | BinaryOperation::Equal
+            | BinaryOperation::NotEqual => {
+                let predicate = Self::compare_predicate(op, is_signed);
+                let result_ir = builder.build_int_compare(predicate, lhs_ir, rhs_ir, "")?;
+                return Ok(BoolValue::new(result_ir).into());
+            }
         };
 
         Ok(Self {
@@ -128,6 +187,22 @@ impl<'ctx> IntegerValue<'ctx> {
         .into())
     }
 
+    fn compare_predicate(op: BinaryOperation, is_signed: bool) -> IntPredicate {
+        match (op, is_signed) {
+            (BinaryOperation::Less, true) => IntPredicate::SLT,
+            (BinaryOperation::Less, false) => IntPredicate::ULT,
+            (BinaryOperation::Greater, true) => IntPredicate::SGT,
+            (BinaryOperation::Greater, false) => IntPredicate::UGT,
+            (BinaryOperation::LessOrEqual, true) => IntPredicate::SLE,
+            (BinaryOperation::LessOrEqual, false) => IntPredicate::ULE,
+            (BinaryOperation::GreaterOrEqual, true) => IntPredicate::SGE,
+            (BinaryOperation::GreaterOrEqual, false) => IntPredicate::UGE,
+            (BinaryOperation::Equal, _) => IntPredicate::EQ,
+            (BinaryOperation::NotEqual, _) => IntPredicate::NE,
+            _ => unreachable!(),
+        }
+    }
+
     pub fn unary_operation(
         self,
         builder: &Builder<'ctx>,
@@ -137,6 +212,9 @@ impl<'ctx> IntegerValue<'ctx> {
             UnaryOperation::Plus => self.ir,
             UnaryOperation::Minus => builder.build_int_neg(self.ir, "")?,
             UnaryOperation::BitNot => builder.build_not(self.ir, "")?,
+            UnaryOperation::LogicalNot => {
+                return self.to_bool(builder)?.unary_operation(builder, op);
+            }
         };
 
         Ok(Self {
@@ -146,6 +224,27 @@ impl<'ctx> IntegerValue<'ctx> {
         .into())
     }
 
+    pub fn cast(
+        self,
+        builder: &Builder<'ctx>,
+        target_type: &Type<'ctx>,
+    ) -> CompilationResult<Value<'ctx>> {
+        Ok(match target_type {
+            Type::Integer(target_type) => self.resize(builder, target_type)?.into(),
+            Type::Float(target_type) => {
+                let target_type_ir = target_type.ir().clone();
+                let result_ir = if self.is_signed {
+                    builder.build_signed_int_to_float(self.ir, target_type_ir, "")?
+                } else {
+                    builder.build_unsigned_int_to_float(self.ir, target_type_ir, "")?
+                };
+                FloatValue::new(result_ir).into()
+            }
+            Type::Bool(_) => self.to_bool(builder)?.into(),
+            _ => return Err(CompilationError::InvalidCast),
+        })
+    }
+
     pub fn extend(
         self,
         builder: &Builder<'ctx>,
@@ -155,11 +254,26 @@ impl<'ctx> IntegerValue<'ctx> {
             return Err(CompilationError::TypeMismatch);
         }
 
-        let target_type_ir = target_type.ir();
-        let result_ir = if target_type.is_signed() {
-            builder.build_int_s_extend(self.ir, target_type_ir.clone(), "")?
+        self.resize(builder, target_type)
+    }
+
+    /// Truncates or extends the value to the width of `target_type`, filling new bits
+    /// according to the signedness of the source. Equal widths only reinterpret the sign.
+    pub fn resize(
+        self,
+        builder: &Builder<'ctx>,
+        target_type: &IntegerType<'ctx>,
+    ) -> CompilationResult<Self> {
+        let value_bit_width = self.ir.get_type().get_bit_width();
+        let target_type_ir = target_type.ir().clone();
+        let result_ir = if target_type.bit_width() < value_bit_width {
+            builder.build_int_truncate(self.ir, target_type_ir, "")?
+        } else if target_type.bit_width() == value_bit_width {
+            self.ir
+        } else if self.is_signed {
+            builder.build_int_s_extend(self.ir, target_type_ir, "")?
         } else {
-            builder.build_int_z_extend(self.ir, target_type_ir.clone(), "")?
+            builder.build_int_z_extend(self.ir, target_type_ir, "")?
         };
 
         Ok(IntegerValue {
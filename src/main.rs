@@ -3,6 +3,8 @@ use inkwell::context::Context;
 use crate::parser::grammar::ModuleParser;
 use crate::target_builder::TargetBuilder;
 
+mod array_type;
+mod array_value;
 mod ast;
 mod block;
 mod bool_value;
@@ -21,11 +23,16 @@ mod integer_value;
 mod module;
 mod module_builder;
 mod parser;
+mod pointer_type;
+mod pointer_value;
 mod statement;
 mod statement_translator;
+mod struct_type;
+mod struct_value;
 mod target_builder;
 mod types;
 mod value;
+mod variable;
 
 const SRC: &'static str = r#"
 proc bar(x: f32): f64 {
@@ -37,7 +44,8 @@ proc foo(x: i32): i32 {
 }
 
 proc test(x: u8, y: i16, z: i32, w: bool): i64 {
-    return foo(x);
+    let a = foo(x);
+    return a;
 }
 "#;
 
@@ -46,9 +54,13 @@ fn main() {
     let module_ast = parser.parse(SRC).unwrap();
 
     let context = Context::create();
-    let target_builder = TargetBuilder::new(&context);
+    let mut target_builder = TargetBuilder::new(&context);
+    target_builder.set_runtime_checks(cfg!(debug_assertions));
     let module = target_builder
         .create_module("test_module", module_ast)
         .unwrap();
+    for warning in target_builder.take_warnings() {
+        eprintln!("warning: {}", warning);
+    }
     module.run_test();
 }
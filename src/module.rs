@@ -29,6 +29,9 @@ impl<'ctx> Module<'ctx> {
 
         type TestFunc = unsafe extern "C" fn(u8, i16, i32, bool) -> i64;
 
+        // Makes symbols of the host process available to `extern proc` declarations.
+        inkwell::support::load_visible_symbols();
+
         let execution_engine = self
             .module_ir
             .create_jit_execution_engine(OptimizationLevel::None)
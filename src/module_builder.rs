@@ -1,19 +1,29 @@
+use std::cell::RefCell;
 use std::ops::Deref;
 
+use inkwell::builder::Builder;
+use inkwell::module::Linkage;
+use inkwell::values::{BasicValue, BasicValueEnum};
+
 use crate::ast;
 use crate::block::Block;
 use crate::definition::Definition;
 use crate::errors::{CompilationError, CompilationResult};
+use crate::expression::Expression;
 use crate::function::Function;
 use crate::function_builder::FunctionBuilder;
 use crate::function_type::FunctionType;
 use crate::module::Module;
+use crate::struct_type::StructType;
 use crate::target_builder::TargetBuilder;
+use crate::types::Type;
 use crate::value::Value;
+use crate::variable::Variable;
 
 pub struct ModuleBuilder<'ctx> {
     parent: &'ctx TargetBuilder<'ctx>,
     module: Module<'ctx>,
+    resolving_aliases: RefCell<Vec<String>>,
 }
 
 impl<'ctx> Deref for ModuleBuilder<'ctx> {
@@ -26,46 +36,319 @@ impl<'ctx> Deref for ModuleBuilder<'ctx> {
 
 impl<'ctx> ModuleBuilder<'ctx> {
     pub fn new(parent: &'ctx TargetBuilder<'ctx>, module: Module<'ctx>) -> Self {
-        Self { parent, module }
+        Self {
+            parent,
+            module,
+            resolving_aliases: RefCell::new(Vec::new()),
+        }
     }
 
-    pub fn define(&mut self, def_ast: ast::Definition) -> CompilationResult<()> {
-        let def = match def_ast.value {
-            ast::DefinitionValue::Function(func_ast) => Definition::Function(
-                self.create_function(def_ast.name.as_str(), func_ast.signature, func_ast.body)?,
-            ),
+    /// Registers a procedure's signature, a struct or a type alias so it can be referenced
+    /// before it is defined.
+    pub fn declare(&mut self, def_ast: &ast::Definition) -> CompilationResult<()> {
+        let func = match &def_ast.value {
+            ast::DefinitionValue::TypeAlias(type_spec) => {
+                let def = Definition::TypeAlias(type_spec.clone());
+                return self.add_definition(&def_ast.name, def);
+            }
+            ast::DefinitionValue::Struct(_) => {
+                let def = Definition::Struct(StructType::declare(self, &def_ast.name));
+                return self.add_definition(&def_ast.name, def);
+            }
+            ast::DefinitionValue::Function(func_ast) => {
+                self.declare_function(&def_ast.name, &func_ast.signature, None)?
+            }
+            // Extern procedures are resolved by the linker, or against the host when JIT-compiled.
+            // Structs and arrays have to be passed to them through pointers.
+            ast::DefinitionValue::ExternFunction(signature) => {
+                let func =
+                    self.declare_function(&def_ast.name, signature, Some(Linkage::External))?;
+                if func.get_type().has_aggregates() {
+                    return Err(CompilationError::ExternAggregate(def_ast.name.clone()));
+                }
+                func
+            }
+            _ => return Ok(()),
         };
-        self.module.add_definition(&def_ast.name, def);
+        self.add_definition(&def_ast.name, Definition::Function(func))
+    }
+
+    pub fn define(&mut self, def_ast: ast::Definition) -> CompilationResult<()> {
+        match def_ast.value {
+            ast::DefinitionValue::Function(func_ast) => {
+                self.build_function(&def_ast.name, func_ast.signature, func_ast.body)
+            }
+            ast::DefinitionValue::ExternFunction(_) => Ok(()),
+            // Resolving the alias once reports cycles even if the alias is never used.
+            ast::DefinitionValue::TypeAlias(_) => self.load_type(&def_ast.name).map(|_| ()),
+            ast::DefinitionValue::Const(const_ast) => {
+                let value_type = Type::from_spec(self, const_ast.value_type)?;
+                let value = self.evaluate_constant(&def_ast.name, &value_type, &const_ast.value)?;
+                self.add_definition(&def_ast.name, Definition::Constant(value))
+            }
+            ast::DefinitionValue::Global(global_ast) => {
+                let global = self.build_global(&def_ast.name, global_ast)?;
+                self.add_definition(&def_ast.name, Definition::Global(global))
+            }
+            ast::DefinitionValue::Struct(struct_ast) => match self.module.defs.get(&def_ast.name) {
+                Some(Definition::Struct(struct_type)) => {
+                    struct_type.set_body(self, &def_ast.name, &struct_ast)
+                }
+                _ => Err(CompilationError::UnresolvedName(def_ast.name)),
+            },
+        }
+    }
 
+    fn add_definition(&mut self, name: &String, def: Definition<'ctx>) -> CompilationResult<()> {
+        if self.module.defs.contains_key(name) {
+            return Err(CompilationError::DuplicateDefinition(name.clone()));
+        }
+
+        self.module.add_definition(name, def);
         Ok(())
     }
 
-    fn create_function(
+    fn build_global(
         &mut self,
         name: &str,
-        func_signature: ast::FunctionSignature,
-        func_body: Block,
+        global_ast: ast::Global,
+    ) -> CompilationResult<Variable<'ctx>> {
+        let value_type = Type::from_spec(self, global_ast.value_type)?;
+        let value = self.evaluate_constant(name, &value_type, &global_ast.value)?;
+        let value_ir: BasicValueEnum = value.try_into()?;
+
+        let global_ir = self.module_ir().add_global(value_ir.get_type(), None, name);
+        global_ir.set_initializer(&value_ir);
+        global_ir.set_thread_local(global_ast.is_thread_local);
+        Ok(Variable::new(
+            global_ir.as_pointer_value(),
+            value_type,
+            true,
+        ))
+    }
+
+    /// Evaluates a constant initializer. It is translated in a scratch procedure, where the
+    /// builder folds constant operands, and rejected unless it folded into a constant. Runtime
+    /// checks are left out, as they would add declarations that outlive the procedure.
+    fn evaluate_constant(
+        &mut self,
+        name: &str,
+        value_type: &Type<'ctx>,
+        expr: &Expression,
+    ) -> CompilationResult<Value<'ctx>> {
+        let signature = ast::FunctionSignature {
+            args: Vec::new(),
+            is_variadic: false,
+            return_type: None,
+        };
+        let func = self.declare_function("", &signature, Some(Linkage::Private))?;
+        let func_ir = func.ir().clone();
+        let value = {
+            let mut func_builder = FunctionBuilder::new(func, signature, self)?;
+            func_builder.set_runtime_checks(false);
+            func_builder.translate_initializer(expr, value_type)
+        };
+        unsafe { func_ir.delete() };
+
+        let value = value?;
+        let value_ir: BasicValueEnum = value.clone().try_into()?;
+        if value_ir.as_instruction_value().is_some() {
+            return Err(CompilationError::NonConstantInitializer(name.to_string()));
+        }
+        Ok(value)
+    }
+
+    fn declare_function(
+        &self,
+        name: &str,
+        func_signature: &ast::FunctionSignature,
+        linkage: Option<Linkage>,
     ) -> CompilationResult<Function<'ctx>> {
-        let func_type = FunctionType::from_ast(self, &func_signature)?;
+        let func_type = FunctionType::from_ast(self, func_signature)?;
         let func_type_ir = func_type.ir().clone();
-        let func_ir = self.module.module_ir.add_function(name, func_type_ir, None);
-        let func = Function::new(func_ir, func_type);
+        let func_ir = self
+            .module
+            .module_ir
+            .add_function(name, func_type_ir, linkage);
+        Ok(Function::new(func_ir, func_type))
+    }
+
+    fn build_function(
+        &mut self,
+        name: &str,
+        func_signature: ast::FunctionSignature,
+        func_body: Block,
+    ) -> CompilationResult<()> {
+        let func = match self.module.defs.get(name) {
+            Some(Definition::Function(func)) => func.clone(),
+            _ => return Err(CompilationError::UnresolvedName(name.to_string())),
+        };
 
         let func_builder = FunctionBuilder::new(func, func_signature, self)?;
-        func_builder.attach_body(func_body)?;
-        Ok(func_builder.build())
+        func_builder.attach_body(func_body)
+    }
+
+    #[inline(always)]
+    pub fn module_ir(&self) -> &inkwell::module::Module<'ctx> {
+        &self.module.module_ir
+    }
+
+    pub fn get_or_declare_function(
+        &self,
+        name: &str,
+        func_type_ir: inkwell::types::FunctionType<'ctx>,
+    ) -> CompilationResult<inkwell::values::FunctionValue<'ctx>> {
+        let module_ir = self.module_ir();
+        match module_ir.get_function(name) {
+            // A procedure of the same name must match, or the call would be invalid IR.
+            Some(func_ir) if func_ir.get_type() == func_type_ir => Ok(func_ir),
+            Some(_) => Err(CompilationError::ConflictingDeclaration(name.to_string())),
+            None => Ok(module_ir.add_function(name, func_type_ir, Some(Linkage::External))),
+        }
     }
 
-    pub fn load_value(&self, name: &str) -> CompilationResult<Value<'ctx>> {
+    /// Resolves a module-level name to its value. Globals are loaded with `builder`, at its
+    /// current position.
+    pub fn load_value(
+        &self,
+        builder: &Builder<'ctx>,
+        name: &str,
+    ) -> CompilationResult<Value<'ctx>> {
         match self.module.defs.get(name) {
             Some(def) => Ok(match def {
                 Definition::Function(func) => func.clone().into(),
+                Definition::Constant(value) => value.clone(),
+                Definition::Global(global) => global.load(builder)?,
+                Definition::Struct(_) | Definition::TypeAlias(_) => {
+                    return Err(CompilationError::UnresolvedName(name.to_string()))
+                }
             }),
             None => Err(CompilationError::UnresolvedName(name.to_string())),
         }
     }
 
+    /// Resolves a module-level `var` to the place it is stored in.
+    pub fn load_global(&self, name: &str) -> Option<Variable<'ctx>> {
+        match self.module.defs.get(name) {
+            Some(Definition::Global(global)) => Some(global.clone()),
+            _ => None,
+        }
+    }
+
+    pub fn load_type(&self, name: &str) -> CompilationResult<Type<'ctx>> {
+        match self.module.defs.get(name) {
+            Some(Definition::Struct(struct_type)) => Ok(struct_type.clone().into()),
+            Some(Definition::TypeAlias(type_spec)) => {
+                if self
+                    .resolving_aliases
+                    .borrow()
+                    .iter()
+                    .any(|alias| alias == name)
+                {
+                    return Err(CompilationError::CyclicTypeAlias(name.to_string()));
+                }
+
+                self.resolving_aliases.borrow_mut().push(name.to_string());
+                let value_type = Type::from_spec(self, type_spec.clone());
+                self.resolving_aliases.borrow_mut().pop();
+                value_type
+            }
+            Some(_) => Err(CompilationError::UnresolvedName(name.to_string())),
+            None => self.parent.load_type(name),
+        }
+    }
+
     pub fn build(self) -> Module<'ctx> {
         self.module
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::errors::CompilationError;
+    use crate::target_builder::tests::compile;
+
+    #[test]
+    fn rejects_initializers_folding_into_poison() {
+        compile("const A: i64 = 1 << 62; const B: i64 = 7 / 2 % 3;").unwrap();
+        assert!(matches!(
+            compile("const B: i64 = 1 / 0;"),
+            Err(CompilationError::DivisionByZero)
+        ));
+        assert!(matches!(
+            compile("var B: u8 = 1u8 % 0;"),
+            Err(CompilationError::DivisionByZero)
+        ));
+        assert!(matches!(
+            compile("const B: i64 = 1 << 70;"),
+            Err(CompilationError::ShiftOverflow(_))
+        ));
+        assert!(matches!(
+            compile("const B: i8 = -128i8 / -1;"),
+            Err(CompilationError::DivisionOverflow(_))
+        ));
+    }
+
+    #[test]
+    fn folds_constant_control_flow() {
+        compile(
+            "const A: bool = true && false;
+             const B: bool = false || 1 < 2;
+             const C: i64 = 1 < 2 ? 5 : 6;
+             var D: f64 = !A ? 1.5 : 2;
+             proc f(x: i64): i64 { return true ? x : 0; }",
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn resolves_constants_in_any_order() {
+        compile(
+            "var C: i64 = B * 2;
+             const B: i64 = A + 1;
+             const A: i64 = 1;",
+        )
+        .unwrap();
+        assert!(matches!(
+            compile("const A: i64 = B; const B: i64 = A;"),
+            Err(CompilationError::CyclicConstant(_))
+        ));
+        assert!(matches!(
+            compile("const A: i64 = A + 1;"),
+            Err(CompilationError::CyclicConstant(_))
+        ));
+    }
+
+    #[test]
+    fn resolves_type_aliases_in_any_order() {
+        compile(
+            "type Id = Foo;
+             struct Foo { id: i64 }
+             type C = B;
+             type B = [A; 2];
+             type A = i64;
+             proc f(x: C, y: Id): i64 { return x[1] + y.id; }",
+        )
+        .unwrap();
+        for src in ["type A = B; type B = A;", "type A = [A; 2];"] {
+            assert!(
+                matches!(compile(src), Err(CompilationError::CyclicTypeAlias(_))),
+                "{src} should be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn resolves_structs_referring_to_each_other() {
+        compile(
+            "struct List { head: *Node }
+             struct Node { value: i64, next: *Node }
+             proc f(list: List): i64 { return (*list.head).value; }",
+        )
+        .unwrap();
+        assert!(matches!(
+            compile("struct A { b: B } struct B { a: A }"),
+            Err(CompilationError::RecursiveStruct(_))
+        ));
+    }
+}
@@ -0,0 +1,95 @@
+use inkwell::context::Context;
+use inkwell::AddressSpace;
+
+use crate::errors::{CompilationError, CompilationResult};
+use crate::pointer_value::PointerValue;
+use crate::types::Type;
+use crate::value::Value;
+
+type PointerTypeIR<'ctx> = inkwell::types::PointerType<'ctx>;
+
+#[derive(Clone, PartialEq)]
+pub struct PointerType<'ctx> {
+    ir: PointerTypeIR<'ctx>,
+    target_type: Option<Box<Type<'ctx>>>,
+    is_mutable: bool,
+}
+
+impl<'ctx> Into<PointerTypeIR<'ctx>> for PointerType<'ctx> {
+    fn into(self) -> PointerTypeIR<'ctx> {
+        self.ir
+    }
+}
+
+impl<'ctx> Into<Type<'ctx>> for PointerType<'ctx> {
+    fn into(self) -> Type<'ctx> {
+        Type::Pointer(self)
+    }
+}
+
+impl<'ctx> PointerType<'ctx> {
+    pub fn new(context: &'ctx Context, target_type: Type<'ctx>, is_mutable: bool) -> Self {
+        PointerType {
+            ir: context.ptr_type(AddressSpace::default()),
+            target_type: Some(Box::new(target_type)),
+            is_mutable,
+        }
+    }
+
+    /// The type of `null`, which converts to every other pointer type.
+    pub fn new_null(context: &'ctx Context) -> Self {
+        PointerType {
+            ir: context.ptr_type(AddressSpace::default()),
+            target_type: None,
+            is_mutable: true,
+        }
+    }
+
+    #[inline(always)]
+    pub fn ir(&self) -> &PointerTypeIR<'ctx> {
+        &self.ir
+    }
+
+    #[inline(always)]
+    pub fn is_null(&self) -> bool {
+        self.target_type.is_none()
+    }
+
+    /// Whether the target can be assigned through this pointer (`*mut T` rather than `*T`).
+    #[inline(always)]
+    pub fn is_mutable(&self) -> bool {
+        self.is_mutable
+    }
+
+    pub fn target_type(&self) -> CompilationResult<&Type<'ctx>> {
+        match &self.target_type {
+            Some(target_type) => Ok(target_type.as_ref()),
+            None => Err(CompilationError::NullDereference),
+        }
+    }
+
+    pub fn validate_value(self, value: Value<'ctx>) -> CompilationResult<PointerValue<'ctx>> {
+        match value {
+            Value::Pointer(value) if self.can_convert_from(value.get_type()) => {
+                Ok(PointerValue::new(value.into(), self))
+            }
+            _ => Err(CompilationError::TypeMismatch),
+        }
+    }
+
+    /// `null` converts to any pointer type, and `*mut T` converts to `*T`.
+    fn can_convert_from(&self, other: &Self) -> bool {
+        other.is_null()
+            || (self.target_type == other.target_type && (other.is_mutable || !self.is_mutable))
+    }
+
+    pub fn combine_with(self, other: Self) -> CompilationResult<Self> {
+        if self.can_convert_from(&other) {
+            Ok(self)
+        } else if other.can_convert_from(&self) {
+            Ok(other)
+        } else {
+            Err(CompilationError::TypeMismatch)
+        }
+    }
+}
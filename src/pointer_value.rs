@@ -0,0 +1,66 @@
+use inkwell::builder::Builder;
+use inkwell::IntPredicate;
+
+use crate::bool_value::BoolValue;
+use crate::errors::{CompilationError, CompilationResult};
+use crate::expression::BinaryOperation;
+use crate::pointer_type::PointerType;
+use crate::value::Value;
+
+type PointerValueIR<'ctx> = inkwell::values::PointerValue<'ctx>;
+
+#[derive(Clone)]
+pub struct PointerValue<'ctx> {
+    ir: PointerValueIR<'ctx>,
+    pointer_type: PointerType<'ctx>,
+}
+
+impl<'ctx> Into<Value<'ctx>> for PointerValue<'ctx> {
+    fn into(self) -> Value<'ctx> {
+        Value::Pointer(self)
+    }
+}
+
+impl<'ctx> Into<PointerValueIR<'ctx>> for PointerValue<'ctx> {
+    fn into(self) -> PointerValueIR<'ctx> {
+        self.ir
+    }
+}
+
+impl<'ctx> PointerValue<'ctx> {
+    pub fn new(ir: PointerValueIR<'ctx>, pointer_type: PointerType<'ctx>) -> Self {
+        PointerValue { ir, pointer_type }
+    }
+
+    pub fn new_null(pointer_type: PointerType<'ctx>) -> Self {
+        PointerValue {
+            ir: pointer_type.ir().const_null(),
+            pointer_type,
+        }
+    }
+
+    pub fn get_type(&self) -> &PointerType<'ctx> {
+        &self.pointer_type
+    }
+
+    pub fn binary_operation(
+        self,
+        builder: &Builder<'ctx>,
+        op: BinaryOperation,
+        other: Value<'ctx>,
+    ) -> CompilationResult<Value<'ctx>> {
+        let other = match other {
+            Value::Pointer(other) => other,
+            _ => return Err(CompilationError::TypeMismatch),
+        };
+
+        self.pointer_type.clone().combine_with(other.pointer_type)?;
+        let predicate = match op {
+            BinaryOperation::Equal => IntPredicate::EQ,
+            BinaryOperation::NotEqual => IntPredicate::NE,
+            _ => return Err(CompilationError::InvalidOperation),
+        };
+        let result_ir = builder.build_int_compare(predicate, self.ir, other.ir, "")?;
+        Ok(BoolValue::new(result_ir).into())
+    }
+}
@@ -1,9 +1,51 @@
 use crate::block::Block;
-use crate::expression::Expression;
+use crate::expression::{BinaryOperation, Expression};
+use crate::types::TypeSpec;
 
 pub enum Statement {
     Block(Block),
-    Return(Box<Expression>),
+    Expression(Box<Expression>),
+    Let(LetStatement),
+    Assign(AssignStatement),
+    If(IfStatement),
+    While(WhileStatement),
+    For(ForStatement),
+    Break,
+    Continue,
+    Return(Option<Box<Expression>>),
+}
+
+pub struct LetStatement {
+    pub name: String,
+    pub is_mutable: bool,
+    pub value_type: Option<TypeSpec>,
+    pub value: Box<Expression>,
+}
+
+pub struct AssignStatement {
+    pub target: Box<Expression>,
+    pub op: Option<BinaryOperation>,
+    pub value: Box<Expression>,
+}
+
+pub struct IfStatement {
+    pub condition: Box<Expression>,
+    pub then_block: Block,
+    pub else_block: Option<Block>,
+}
+
+pub struct WhileStatement {
+    pub condition: Box<Expression>,
+    pub body: Block,
+}
+
+pub struct ForStatement {
+    pub name: String,
+    pub start: Box<Expression>,
+    pub end: Box<Expression>,
+    pub is_inclusive: bool,
+    pub step: Option<Box<Expression>>,
+    pub body: Block,
 }
 
 impl Statement {
@@ -11,7 +53,75 @@ impl Statement {
         Statement::Block(block)
     }
 
-    pub fn new_return(expr: Box<Expression>) -> Statement {
+    pub fn new_let(
+        name: String,
+        is_mutable: bool,
+        value_type: Option<TypeSpec>,
+        value: Box<Expression>,
+    ) -> Statement {
+        Statement::Let(LetStatement {
+            name,
+            is_mutable,
+            value_type,
+            value,
+        })
+    }
+
+    pub fn new_assign(
+        target: Box<Expression>,
+        op: Option<BinaryOperation>,
+        value: Box<Expression>,
+    ) -> Statement {
+        Statement::Assign(AssignStatement { target, op, value })
+    }
+
+    pub fn new_if(
+        condition: Box<Expression>,
+        then_block: Block,
+        else_block: Option<Block>,
+    ) -> Statement {
+        Statement::If(IfStatement {
+            condition,
+            then_block,
+            else_block,
+        })
+    }
+
+    pub fn new_while(condition: Box<Expression>, body: Block) -> Statement {
+        Statement::While(WhileStatement { condition, body })
+    }
+
+    pub fn new_for(
+        name: String,
+        start: Box<Expression>,
+        end: Box<Expression>,
+        is_inclusive: bool,
+        step: Option<Box<Expression>>,
+        body: Block,
+    ) -> Statement {
+        Statement::For(ForStatement {
+            name,
+            start,
+            end,
+            is_inclusive,
+            step,
+            body,
+        })
+    }
+
+    pub fn new_expression(expr: Box<Expression>) -> Statement {
+        Statement::Expression(expr)
+    }
+
+    pub fn new_break() -> Statement {
+        Statement::Break
+    }
+
+    pub fn new_continue() -> Statement {
+        Statement::Continue
+    }
+
+    pub fn new_return(expr: Option<Box<Expression>>) -> Statement {
         Statement::Return(expr)
     }
 }
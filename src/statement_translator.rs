@@ -1,19 +1,35 @@
+use std::cell::RefCell;
 use std::collections::HashMap;
 use std::ops::Deref;
 
-use inkwell::values::BasicValueEnum;
+use inkwell::basic_block::BasicBlock;
+use inkwell::values::{BasicValueEnum, IntValue};
+use inkwell::IntPredicate;
 
 use crate::block::Block;
 use crate::block::BlockVisitor;
-use crate::errors::CompilationResult;
+use crate::errors::{CompilationError, CompilationResult, CompilationWarning};
 use crate::expression::Expression;
 use crate::expression_translator::ExpressionTranslator;
 use crate::function_builder::FunctionBuilder;
+use crate::integer_type::IntegerType;
+use crate::integer_value::IntegerValue;
+use crate::statement::{AssignStatement, ForStatement, IfStatement, LetStatement, WhileStatement};
+use crate::types::Type;
 use crate::value::Value;
+use crate::variable::Variable;
+
+#[derive(Clone, Copy)]
+struct LoopTarget<'ctx> {
+    continue_block_ir: BasicBlock<'ctx>,
+    break_block_ir: BasicBlock<'ctx>,
+}
 
 pub struct StatementTranslator<'ctx, 'm, 'f> {
     parent: &'f FunctionBuilder<'ctx, 'm>,
-    values: HashMap<String, Value<'ctx>>,
+    parent_scope: Option<&'f StatementTranslator<'ctx, 'm, 'f>>,
+    loop_target: Option<LoopTarget<'ctx>>,
+    variables: RefCell<HashMap<String, Variable<'ctx>>>,
 }
 
 impl<'ctx, 'm, 'f> Deref for StatementTranslator<'ctx, 'm, 'f> {
@@ -25,18 +41,268 @@ impl<'ctx, 'm, 'f> Deref for StatementTranslator<'ctx, 'm, 'f> {
 }
 
 impl<'ctx, 'm, 'f> BlockVisitor for StatementTranslator<'ctx, 'm, 'f> {
+    fn is_terminated(&self) -> bool {
+        self.parent.is_terminated()
+    }
+
+    fn enter_unreachable_code(&self, is_first: bool) -> CompilationResult<()> {
+        if is_first {
+            self.add_warning(CompilationWarning::UnreachableCode(self.function_name()));
+        }
+
+        let detached_block_ir = self
+            .context()
+            .append_basic_block(self.function_ir().clone(), "");
+        self.builder().position_at_end(detached_block_ir);
+        Ok(())
+    }
+
+    fn leave_unreachable_code(&self) -> CompilationResult<()> {
+        if !self.is_terminated() {
+            self.builder().build_unreachable()?;
+        }
+        Ok(())
+    }
+
     fn enter_block(&self, block: &Block) -> CompilationResult<()> {
-        block.visit(self)
+        let block_translator = StatementTranslator::new_scope(self);
+        block.visit(&block_translator)
     }
 
-    fn add_return_statement(&self, expr: &Expression) -> CompilationResult<()> {
+    fn add_let_statement(&self, stmt: &LetStatement) -> CompilationResult<()> {
         let expr_translator = ExpressionTranslator::new(self);
-        let expr_type = self.function_return_type().clone();
+        let (value, value_type) = match &stmt.value_type {
+            Some(type_spec) => {
+                let value_type = Type::from_spec(self, type_spec.clone())?;
+                let value = expr_translator.translate_expression(&stmt.value, Some(&value_type))?;
+                (value, value_type)
+            }
+            None => {
+                let value = expr_translator.translate_expression(&stmt.value, None)?;
+                let value_type = value.get_type();
+                (value, value_type)
+            }
+        };
+
+        let variable = self.allocate_variable(&stmt.name, value_type, stmt.is_mutable)?;
+        variable.store(self.builder(), value)?;
+        self.add_variable(stmt.name.clone(), variable);
+
+        Ok(())
+    }
+
+    fn add_assign_statement(&self, stmt: &AssignStatement) -> CompilationResult<()> {
+        let variable = self.load_assignable_variable(&stmt.target)?;
+        let value_type = variable.get_type().clone();
+
+        let expr_translator = ExpressionTranslator::new(self);
+        let value = expr_translator.translate_expression(&stmt.value, Some(&value_type))?;
+        let value = match stmt.op {
+            Some(op) => {
+                let builder = self.builder();
+                let current_value = variable.load(builder)?;
+                current_value
+                    .binary_operation(builder, op, value)?
+                    .validate_type(builder, value_type)?
+            }
+            None => value,
+        };
+
+        variable.store(self.builder(), value)
+    }
+
+    fn add_if_statement(&self, stmt: &IfStatement) -> CompilationResult<()> {
+        let expr_translator = ExpressionTranslator::new(self);
+        let condition = expr_translator.translate_condition(&stmt.condition)?;
+
+        let context = self.context();
+        let builder = self.builder();
+        let func_ir = self.function_ir().clone();
+        let then_block_ir = context.append_basic_block(func_ir, "");
+        let else_block_ir = context.append_basic_block(func_ir, "");
+        builder.build_conditional_branch(condition.into(), then_block_ir, else_block_ir)?;
+
+        let mut merge_block_ir = None;
+
+        builder.position_at_end(then_block_ir);
+        self.enter_block(&stmt.then_block)?;
+        self.build_branch_to_merge(&mut merge_block_ir)?;
+
+        builder.position_at_end(else_block_ir);
+        if let Some(else_block) = &stmt.else_block {
+            self.enter_block(else_block)?;
+        }
+        self.build_branch_to_merge(&mut merge_block_ir)?;
+
+        // When every branch returns there is nothing to merge and the rest of the
+        // enclosing block is unreachable.
+        if let Some(merge_block_ir) = merge_block_ir {
+            builder.position_at_end(merge_block_ir);
+        }
+
+        Ok(())
+    }
+
+    fn add_while_statement(&self, stmt: &WhileStatement) -> CompilationResult<()> {
+        let context = self.context();
+        let builder = self.builder();
+        let func_ir = self.function_ir().clone();
+        let header_block_ir = context.append_basic_block(func_ir, "");
+        let body_block_ir = context.append_basic_block(func_ir, "");
+        let exit_block_ir = context.append_basic_block(func_ir, "");
+        builder.build_unconditional_branch(header_block_ir)?;
+
+        builder.position_at_end(header_block_ir);
+        let expr_translator = ExpressionTranslator::new(self);
+        let condition_ir: IntValue = expr_translator.translate_condition(&stmt.condition)?.into();
+        // A constant condition branches unconditionally, so that the exit of `while true` is
+        // only reached through `break`.
+        match condition_ir.get_zero_extended_constant() {
+            Some(0) => builder.build_unconditional_branch(exit_block_ir)?,
+            Some(_) => builder.build_unconditional_branch(body_block_ir)?,
+            None => builder.build_conditional_branch(condition_ir, body_block_ir, exit_block_ir)?,
+        };
+
+        builder.position_at_end(body_block_ir);
+        let loop_target = LoopTarget {
+            continue_block_ir: header_block_ir,
+            break_block_ir: exit_block_ir,
+        };
+        let body_translator = StatementTranslator::new_loop_scope(self, loop_target);
+        stmt.body.visit(&body_translator)?;
+        if !self.is_terminated() {
+            builder.build_unconditional_branch(header_block_ir)?;
+        }
+
+        builder.position_at_end(exit_block_ir);
+        Ok(())
+    }
+
+    fn add_for_statement(&self, stmt: &ForStatement) -> CompilationResult<()> {
+        let expr_translator = ExpressionTranslator::new(self);
+        let (start, end) = match expr_translator.translate_operands(&stmt.start, &stmt.end, None)? {
+            (Value::Integer(start), Value::Integer(end)) => (start, end),
+            _ => return Err(CompilationError::TypeMismatch),
+        };
+        let value_type = start.get_type().combine_with(end.get_type())?;
+        let is_signed = value_type.is_signed();
+
+        let builder = self.builder();
+        let start = start.extend(builder, &value_type)?;
+        let end_ir: IntValue = end.extend(builder, &value_type)?.into();
+        let step_ir: IntValue = match &stmt.step {
+            Some(step) => expr_translator
+                .translate_integer(step, Some(&value_type))?
+                .into(),
+            None => value_type.ir().const_int(1, false),
+        };
+        // A step that is not positive would never reach the end of the range.
+        match step_ir.get_sign_extended_constant() {
+            Some(step) if is_signed && step <= 0 => return Err(CompilationError::InvalidStep),
+            Some(_) if step_ir.get_zero_extended_constant() == Some(0) => {
+                return Err(CompilationError::InvalidStep)
+            }
+            Some(_) => {}
+            None => self.build_step_check(step_ir, is_signed)?,
+        }
+
+        let variable =
+            self.allocate_variable(&stmt.name, Type::Integer(value_type.clone()), false)?;
+        let start_ir: IntValue = start.clone().into();
+        variable.store(builder, start.into())?;
+
+        let context = self.context();
+        let func_ir = self.function_ir().clone();
+        let body_block_ir = context.append_basic_block(func_ir, "");
+        let latch_block_ir = context.append_basic_block(func_ir, "");
+        let exit_block_ir = context.append_basic_block(func_ir, "");
+
+        let enter_predicate = match (stmt.is_inclusive, is_signed) {
+            (false, false) => IntPredicate::ULT,
+            (false, true) => IntPredicate::SLT,
+            (true, false) => IntPredicate::ULE,
+            (true, true) => IntPredicate::SLE,
+        };
+        let is_not_empty_ir = builder.build_int_compare(enter_predicate, start_ir, end_ir, "")?;
+        builder.build_conditional_branch(is_not_empty_ir, body_block_ir, exit_block_ir)?;
+
+        builder.position_at_end(body_block_ir);
+        let loop_target = LoopTarget {
+            continue_block_ir: latch_block_ir,
+            break_block_ir: exit_block_ir,
+        };
+        let body_translator = StatementTranslator::new_loop_scope(self, loop_target);
+        body_translator.add_variable(stmt.name.clone(), variable.clone());
+        stmt.body.visit(&body_translator)?;
+        if !self.is_terminated() {
+            builder.build_unconditional_branch(latch_block_ir)?;
+        }
+
+        // The distance to the end is compared against the step instead of advancing first,
+        // so the counter never wraps around when the range ends at the type's maximum value.
+        builder.position_at_end(latch_block_ir);
+        let current_ir: IntValue = match variable.load(builder)? {
+            Value::Integer(value) => value.into(),
+            _ => return Err(CompilationError::TypeMismatch),
+        };
+        let remaining_ir = builder.build_int_sub(end_ir, current_ir, "")?;
+        let exit_predicate = if stmt.is_inclusive {
+            IntPredicate::ULT
+        } else {
+            IntPredicate::ULE
+        };
+        let is_finished_ir =
+            builder.build_int_compare(exit_predicate, remaining_ir, step_ir, "")?;
+        let next_ir = builder.build_int_add(current_ir, step_ir, "")?;
+        variable.store(builder, IntegerValue::new(next_ir, is_signed).into())?;
+        builder.build_conditional_branch(is_finished_ir, exit_block_ir, body_block_ir)?;
+
+        builder.position_at_end(exit_block_ir);
+        Ok(())
+    }
+
+    fn add_expression_statement(&self, expr: &Expression) -> CompilationResult<()> {
+        let expr_translator = ExpressionTranslator::new(self);
+        let value = expr_translator.translate_expression(expr, None)?;
+        if !matches!(value, Value::Unit) {
+            self.add_warning(CompilationWarning::UnusedResult(self.function_name()));
+        }
+        Ok(())
+    }
 
-        let value = expr_translator.translate_expression(expr, Some(&expr_type))?;
-        let value_ir: BasicValueEnum<'ctx> = value.try_into()?;
+    fn add_break_statement(&self) -> CompilationResult<()> {
+        let loop_target = self
+            .find_loop_target()
+            .ok_or_else(|| CompilationError::OutsideOfLoop("break".to_string()))?;
+        self.builder()
+            .build_unconditional_branch(loop_target.break_block_ir)?;
+        Ok(())
+    }
 
-        self.builder().build_return(Some(&value_ir))?;
+    fn add_continue_statement(&self) -> CompilationResult<()> {
+        let loop_target = self
+            .find_loop_target()
+            .ok_or_else(|| CompilationError::OutsideOfLoop("continue".to_string()))?;
+        self.builder()
+            .build_unconditional_branch(loop_target.continue_block_ir)?;
+        Ok(())
+    }
+
+    fn add_return_statement(&self, expr: Option<&Expression>) -> CompilationResult<()> {
+        let expr_translator = ExpressionTranslator::new(self);
+        let expr_type = self.function_return_type().clone();
+
+        let value = match expr {
+            Some(expr) => expr_translator.translate_expression(expr, Some(&expr_type))?,
+            None => Value::Unit.validate_type(self.builder(), expr_type)?,
+        };
+        match value {
+            Value::Unit => self.builder().build_return(None)?,
+            value => {
+                let value_ir: BasicValueEnum<'ctx> = value.try_into()?;
+                self.builder().build_return(Some(&value_ir))?
+            }
+        };
         Ok(())
     }
 }
@@ -45,14 +311,208 @@ impl<'ctx, 'm, 'f> StatementTranslator<'ctx, 'm, 'f> {
     pub fn new(parent: &'f FunctionBuilder<'ctx, 'm>) -> Self {
         Self {
             parent,
-            values: HashMap::new(),
+            parent_scope: None,
+            loop_target: None,
+            variables: RefCell::new(HashMap::new()),
+        }
+    }
+
+    fn new_scope(parent_scope: &'f StatementTranslator<'ctx, 'm, 'f>) -> Self {
+        Self {
+            parent: parent_scope.parent,
+            parent_scope: Some(parent_scope),
+            loop_target: None,
+            variables: RefCell::new(HashMap::new()),
+        }
+    }
+
+    fn new_loop_scope(
+        parent_scope: &'f StatementTranslator<'ctx, 'm, 'f>,
+        loop_target: LoopTarget<'ctx>,
+    ) -> Self {
+        Self {
+            parent: parent_scope.parent,
+            parent_scope: Some(parent_scope),
+            loop_target: Some(loop_target),
+            variables: RefCell::new(HashMap::new()),
+        }
+    }
+
+    fn find_loop_target(&self) -> Option<LoopTarget<'ctx>> {
+        match self.loop_target {
+            Some(loop_target) => Some(loop_target),
+            None => self
+                .parent_scope
+                .and_then(|parent_scope| parent_scope.find_loop_target()),
+        }
+    }
+
+    fn build_branch_to_merge(
+        &self,
+        merge_block_ir: &mut Option<BasicBlock<'ctx>>,
+    ) -> CompilationResult<()> {
+        if self.is_terminated() {
+            return Ok(());
+        }
+
+        let merge_block_ir = *merge_block_ir.get_or_insert_with(|| {
+            self.context()
+                .append_basic_block(self.function_ir().clone(), "")
+        });
+        self.builder().build_unconditional_branch(merge_block_ir)?;
+        Ok(())
+    }
+
+    pub fn add_variable(&self, name: String, variable: Variable<'ctx>) {
+        self.variables.borrow_mut().insert(name, variable);
+    }
+
+    pub fn load_variable(&self, name: &str) -> Option<Variable<'ctx>> {
+        if let Some(variable) = self.variables.borrow().get(name) {
+            return Some(variable.clone());
+        }
+
+        match self.parent_scope {
+            Some(parent_scope) => parent_scope.load_variable(name),
+            None => self.parent.load_global(name),
         }
     }
 
     pub fn load_value(&self, name: &str) -> CompilationResult<Value<'ctx>> {
-        match self.values.get(name) {
-            Some(value) => Ok(value.clone()),
+        match self.load_variable(name) {
+            Some(variable) => variable.load(self.builder()),
             None => self.parent.load_value(name),
         }
     }
+
+    /// Resolves an expression that designates storage (a local variable, or a field or an
+    /// element of one) without loading it. Returns `None` for any other expression.
+    pub fn load_place(&self, expr: &Expression) -> CompilationResult<Option<Variable<'ctx>>> {
+        Ok(match expr {
+            Expression::LoadValue(name) => self.load_variable(name),
+            Expression::FieldAccess(expr) => match self.load_place(&expr.value)? {
+                Some(variable) => Some(variable.get_field(self.builder(), &expr.name)?),
+                None => None,
+            },
+            Expression::Index(expr) => match self.load_place(&expr.value)? {
+                Some(variable) => Some(self.build_element_place(&variable, &expr.index)?),
+                None => None,
+            },
+            Expression::Dereference(expr) => Some(self.build_dereference_place(expr)?),
+            _ => None,
+        })
+    }
+
+    pub fn build_element_place(
+        &self,
+        variable: &Variable<'ctx>,
+        index_expr: &Expression,
+    ) -> CompilationResult<Variable<'ctx>> {
+        let len = match variable.get_type() {
+            Type::Array(array_type) => array_type.len(),
+            _ => return Err(CompilationError::InvalidOperation),
+        };
+
+        let builder = self.builder();
+        let expr_translator = ExpressionTranslator::new(self);
+        let index_type = IntegerType::new_i64(self.context(), true);
+        let index_ir: IntValue = expr_translator
+            .translate_integer(index_expr, None)?
+            .resize(builder, &index_type)?
+            .into();
+
+        match index_ir.get_sign_extended_constant() {
+            Some(index) if index < 0 || index >= len as i64 => {
+                return Err(CompilationError::IndexOutOfBounds(index, len))
+            }
+            Some(_) => {}
+            None => self.build_bounds_check(index_ir, len)?,
+        }
+
+        variable.get_element(builder, index_ir)
+    }
+
+    fn build_dereference_place(
+        &self,
+        pointer_expr: &Expression,
+    ) -> CompilationResult<Variable<'ctx>> {
+        let expr_translator = ExpressionTranslator::new(self);
+        let pointer = match expr_translator.translate_expression(pointer_expr, None)? {
+            Value::Pointer(pointer) => pointer,
+            _ => return Err(CompilationError::InvalidOperation),
+        };
+
+        let pointer_type = pointer.get_type();
+        let target_type = pointer_type.target_type()?.clone();
+        let is_mutable = pointer_type.is_mutable();
+        let pointer_ir = pointer.into();
+        self.build_null_check(pointer_ir)?;
+        Ok(Variable::new(pointer_ir, target_type, is_mutable))
+    }
+
+    fn load_assignable_variable(&self, target: &Expression) -> CompilationResult<Variable<'ctx>> {
+        if let Some(variable) = self.load_place(target)? {
+            if !variable.is_mutable() {
+                return Err(match Self::root_name(target) {
+                    Some(name) => CompilationError::ImmutableAssignment(name.clone()),
+                    None => CompilationError::ImmutablePointerAssignment,
+                });
+            }
+            return Ok(variable);
+        }
+
+        match Self::root_name(target) {
+            Some(name) => match self.parent.load_value(name)? {
+                Value::Function(_) => Err(CompilationError::InvalidAssignment(name.clone())),
+                _ => Err(CompilationError::ImmutableAssignment(name.clone())),
+            },
+            None => Err(CompilationError::InvalidOperation),
+        }
+    }
+
+    fn root_name(expr: &Expression) -> Option<&String> {
+        match expr {
+            Expression::LoadValue(name) => Some(name),
+            Expression::FieldAccess(expr) => Self::root_name(&expr.value),
+            Expression::Index(expr) => Self::root_name(&expr.value),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::errors::CompilationError;
+    use crate::target_builder::tests::compile;
+
+    #[test]
+    fn types_loop_bounds_from_each_other() {
+        compile(
+            "proc f(n: u8): u8 {
+                 var sum: u8 = 0;
+                 for i in 0..n { sum = sum + i; }
+                 for i in 1..=n step 2 { sum = sum + i; }
+                 return sum;
+             }",
+        )
+        .unwrap();
+        assert!(matches!(
+            compile("proc f(n: u8) { for i in n..300 { } }"),
+            Err(CompilationError::LiteralOutOfRange(_))
+        ));
+    }
+
+    #[test]
+    fn rejects_non_positive_steps() {
+        compile("proc f(n: i64, s: i64) { for i in 0..n step s { } }").unwrap();
+        for src in [
+            "proc f(n: i64) { for i in 0..n step 0 { } }",
+            "proc f(n: i64) { for i in 0..n step -1 { } }",
+        ] {
+            assert!(
+                matches!(compile(src), Err(CompilationError::InvalidStep)),
+                "{src} should be rejected"
+            );
+        }
+    }
 }
@@ -0,0 +1,122 @@
+use std::cell::{Ref, RefCell};
+use std::rc::Rc;
+
+use inkwell::types::BasicTypeEnum;
+
+use crate::ast;
+use crate::errors::{CompilationError, CompilationResult};
+use crate::module_builder::ModuleBuilder;
+use crate::struct_value::StructValue;
+use crate::types::Type;
+use crate::value::Value;
+
+type StructTypeIR<'ctx> = inkwell::types::StructType<'ctx>;
+
+#[derive(Clone)]
+pub struct StructType<'ctx> {
+    ir: StructTypeIR<'ctx>,
+    fields: Rc<RefCell<Vec<(String, Type<'ctx>)>>>,
+}
+
+/// Named LLVM structs are unique, so comparing them is enough. Comparing the fields would
+/// never finish for a struct that points to itself.
+impl<'ctx> PartialEq for StructType<'ctx> {
+    fn eq(&self, other: &Self) -> bool {
+        self.ir == other.ir
+    }
+}
+
+impl<'ctx> Into<StructTypeIR<'ctx>> for StructType<'ctx> {
+    fn into(self) -> StructTypeIR<'ctx> {
+        self.ir
+    }
+}
+
+impl<'ctx> Into<Type<'ctx>> for StructType<'ctx> {
+    fn into(self) -> Type<'ctx> {
+        Type::Struct(self)
+    }
+}
+
+impl<'ctx> StructType<'ctx> {
+    /// Creates the struct without a body, so that it can be referenced before its fields are
+    /// resolved, including by its own fields.
+    pub fn declare(module_builder: &ModuleBuilder<'ctx>, name: &str) -> Self {
+        StructType {
+            ir: module_builder.context().opaque_struct_type(name),
+            fields: Rc::new(RefCell::new(Vec::new())),
+        }
+    }
+
+    /// Fields keep their declaration order and natural alignment, which gives the same
+    /// layout as the equivalent `#[repr(C)]` struct. Extern procedures must take and return
+    /// structs through pointers, as by-value structs don't follow the C calling convention.
+    pub fn set_body(
+        &self,
+        module_builder: &ModuleBuilder<'ctx>,
+        name: &str,
+        struct_ast: &ast::Struct,
+    ) -> CompilationResult<()> {
+        let fields_count = struct_ast.fields.len();
+        let mut fields = Vec::with_capacity(fields_count);
+        let mut field_types_ir = Vec::with_capacity(fields_count);
+        for field_ast in struct_ast.fields.iter() {
+            if fields.iter().any(|(name, _)| name == &field_ast.name) {
+                return Err(CompilationError::DuplicateField(field_ast.name.clone()));
+            }
+
+            let field_type = Type::from_spec(module_builder, field_ast.value_type.clone())?;
+            let field_type_ir: BasicTypeEnum = field_type.clone().try_into()?;
+            fields.push((field_ast.name.clone(), field_type));
+            field_types_ir.push(field_type_ir);
+        }
+
+        *self.fields.borrow_mut() = fields;
+        if self.contains(self) {
+            return Err(CompilationError::RecursiveStruct(name.to_string()));
+        }
+
+        self.ir.set_body(&field_types_ir, false);
+        Ok(())
+    }
+
+    /// Whether `other` is stored by value in this struct, directly or through nested structs
+    /// and arrays. Pointers are not followed, as they are what breaks a recursion.
+    fn contains(&self, other: &StructType<'ctx>) -> bool {
+        self.fields.borrow().iter().any(|(_, field_type)| {
+            let mut field_type = field_type;
+            while let Type::Array(array_type) = field_type {
+                field_type = array_type.element_type();
+            }
+            match field_type {
+                Type::Struct(struct_type) => struct_type == other || struct_type.contains(other),
+                _ => false,
+            }
+        })
+    }
+
+    #[inline(always)]
+    pub fn ir(&self) -> &StructTypeIR<'ctx> {
+        &self.ir
+    }
+
+    #[inline(always)]
+    pub fn fields(&self) -> Ref<'_, Vec<(String, Type<'ctx>)>> {
+        self.fields.borrow()
+    }
+
+    pub fn get_field(&self, name: &str) -> CompilationResult<(u32, Type<'ctx>)> {
+        let fields = self.fields.borrow();
+        match fields.iter().position(|(field_name, _)| field_name == name) {
+            Some(index) => Ok((index as u32, fields[index].1.clone())),
+            None => Err(CompilationError::UnknownField(name.to_string())),
+        }
+    }
+
+    pub fn validate_value(self, value: Value<'ctx>) -> CompilationResult<StructValue<'ctx>> {
+        match value {
+            Value::Struct(value) if value.get_type() == &self => Ok(value),
+            _ => Err(CompilationError::TypeMismatch),
+        }
+    }
+}
@@ -0,0 +1,59 @@
+use inkwell::builder::Builder;
+use inkwell::values::{AnyValue, BasicValueEnum};
+
+use crate::errors::CompilationResult;
+use crate::struct_type::StructType;
+use crate::value::Value;
+
+type StructValueIR<'ctx> = inkwell::values::StructValue<'ctx>;
+
+#[derive(Clone)]
+pub struct StructValue<'ctx> {
+    ir: StructValueIR<'ctx>,
+    struct_type: StructType<'ctx>,
+}
+
+impl<'ctx> Into<Value<'ctx>> for StructValue<'ctx> {
+    fn into(self) -> Value<'ctx> {
+        Value::Struct(self)
+    }
+}
+
+impl<'ctx> Into<StructValueIR<'ctx>> for StructValue<'ctx> {
+    fn into(self) -> StructValueIR<'ctx> {
+        self.ir
+    }
+}
+
+impl<'ctx> StructValue<'ctx> {
+    pub fn new(ir: StructValueIR<'ctx>, struct_type: StructType<'ctx>) -> Self {
+        StructValue { ir, struct_type }
+    }
+
+    pub fn get_type(&self) -> &StructType<'ctx> {
+        &self.struct_type
+    }
+
+    pub fn get_field(&self, builder: &Builder<'ctx>, name: &str) -> CompilationResult<Value<'ctx>> {
+        let (field_id, field_type) = self.struct_type.get_field(name)?;
+        let field_ir = builder.build_extract_value(self.ir, field_id, "")?;
+        Value::from_ir(field_ir.as_any_value_enum(), &field_type)
+    }
+
+    pub fn set_field(
+        self,
+        builder: &Builder<'ctx>,
+        name: &str,
+        value: Value<'ctx>,
+    ) -> CompilationResult<Self> {
+        let (field_id, field_type) = self.struct_type.get_field(name)?;
+        let field_ir: BasicValueEnum = value.validate_type(builder, field_type)?.try_into()?;
+        let result_ir = builder
+            .build_insert_value(self.ir, field_ir, field_id, "")?
+            .into_struct_value();
+        Ok(StructValue {
+            ir: result_ir,
+            struct_type: self.struct_type,
+        })
+    }
+}
@@ -1,10 +1,11 @@
+use std::cell::RefCell;
 use std::collections::HashMap;
 
 use inkwell::context::Context;
 use inkwell::targets::TargetTriple;
 
 use crate::ast;
-use crate::errors::{CompilationError, CompilationResult};
+use crate::errors::{CompilationError, CompilationResult, CompilationWarning};
 use crate::module::Module;
 use crate::module_builder::ModuleBuilder;
 use crate::types::Type;
@@ -12,6 +13,8 @@ use crate::types::Type;
 pub struct TargetBuilder<'ctx> {
     context: &'ctx Context,
     builtin_types: HashMap<String, Type<'ctx>>,
+    runtime_checks: bool,
+    warnings: RefCell<Vec<CompilationWarning>>,
 }
 
 impl<'ctx> TargetBuilder<'ctx> {
@@ -33,32 +36,158 @@ impl<'ctx> TargetBuilder<'ctx> {
         TargetBuilder {
             context,
             builtin_types,
+            runtime_checks: true,
+            warnings: RefCell::new(Vec::new()),
         }
     }
 
+    /// Enables or disables runtime checks, such as array bounds checks, in generated code.
+    pub fn set_runtime_checks(&mut self, enabled: bool) {
+        self.runtime_checks = enabled;
+    }
+
+    #[inline(always)]
+    pub fn runtime_checks(&self) -> bool {
+        self.runtime_checks
+    }
+
+    pub fn add_warning(&self, warning: CompilationWarning) {
+        self.warnings.borrow_mut().push(warning);
+    }
+
+    /// Returns the warnings reported so far and clears them.
+    pub fn take_warnings(&self) -> Vec<CompilationWarning> {
+        self.warnings.take()
+    }
+
     #[inline(always)]
     pub fn context(&self) -> &'ctx Context {
         self.context
     }
 
     pub fn create_module(
-        &self,
+        &'ctx self,
         name: &str,
         module_ast: ast::Module,
-    ) -> CompilationResult<Module<'_>> {
+    ) -> CompilationResult<Module<'ctx>> {
         let module_ir = self.context.create_module(name);
         module_ir.set_triple(&TargetTriple::create("x86_64-pc-linux-gnu"));
 
         let module = Module::new(module_ir);
 
         let mut module_builder = ModuleBuilder::new(self, module);
-        for def_ast in module_ast.defs {
+
+        // Types come first so that any signature can use them, followed by constants and
+        // globals, sorted so that initializers may use constants defined after them. Structs
+        // and type aliases are all registered before any field or alias is resolved, so they
+        // may refer to each other in any order and a struct may point to itself. Procedure
+        // signatures are then declared before any body is built, which allows recursion and
+        // forward references.
+        let (type_defs, defs): (Vec<_>, Vec<_>) =
+            module_ast.defs.into_iter().partition(|def_ast| {
+                matches!(
+                    def_ast.value,
+                    ast::DefinitionValue::Struct(_) | ast::DefinitionValue::TypeAlias(_)
+                )
+            });
+        let (value_defs, defs): (Vec<_>, Vec<_>) = defs.into_iter().partition(|def_ast| {
+            matches!(
+                def_ast.value,
+                ast::DefinitionValue::Const(_) | ast::DefinitionValue::Global(_)
+            )
+        });
+        for def_ast in type_defs.iter() {
+            module_builder.declare(def_ast)?;
+        }
+        let value_defs = Self::sort_value_defs(value_defs)?;
+        for def_ast in type_defs.into_iter().chain(value_defs) {
+            module_builder.define(def_ast)?;
+        }
+        for def_ast in defs.iter() {
+            module_builder.declare(def_ast)?;
+        }
+        for def_ast in defs {
             module_builder.define(def_ast)?;
         }
 
         Ok(module_builder.build())
     }
 
+    /// Orders constants and globals so that each initializer comes after the constants it
+    /// uses, whatever order they are written in.
+    fn sort_value_defs(
+        value_defs: Vec<ast::Definition>,
+    ) -> CompilationResult<Vec<ast::Definition>> {
+        let mut const_ids = HashMap::new();
+        for (id, def_ast) in value_defs.iter().enumerate() {
+            if let ast::DefinitionValue::Const(_) = def_ast.value {
+                const_ids.entry(def_ast.name.as_str()).or_insert(id);
+            }
+        }
+
+        let mut order = Vec::with_capacity(value_defs.len());
+        let mut is_visited = vec![false; value_defs.len()];
+        let mut is_visiting = vec![false; value_defs.len()];
+        for id in 0..value_defs.len() {
+            Self::visit_value_def(
+                id,
+                &value_defs,
+                &const_ids,
+                &mut is_visited,
+                &mut is_visiting,
+                &mut order,
+            )?;
+        }
+
+        let mut value_defs: Vec<_> = value_defs.into_iter().map(Some).collect();
+        Ok(order
+            .into_iter()
+            .map(|id| value_defs[id].take().unwrap())
+            .collect())
+    }
+
+    fn visit_value_def(
+        id: usize,
+        value_defs: &[ast::Definition],
+        const_ids: &HashMap<&str, usize>,
+        is_visited: &mut [bool],
+        is_visiting: &mut [bool],
+        order: &mut Vec<usize>,
+    ) -> CompilationResult<()> {
+        if is_visited[id] {
+            return Ok(());
+        }
+        if is_visiting[id] {
+            return Err(CompilationError::CyclicConstant(
+                value_defs[id].name.clone(),
+            ));
+        }
+
+        is_visiting[id] = true;
+        let mut names = Vec::new();
+        match &value_defs[id].value {
+            ast::DefinitionValue::Const(const_ast) => const_ast.value.collect_names(&mut names),
+            ast::DefinitionValue::Global(global_ast) => global_ast.value.collect_names(&mut names),
+            _ => {}
+        }
+        for name in names {
+            if let Some(&dependency_id) = const_ids.get(name) {
+                Self::visit_value_def(
+                    dependency_id,
+                    value_defs,
+                    const_ids,
+                    is_visited,
+                    is_visiting,
+                    order,
+                )?;
+            }
+        }
+        is_visiting[id] = false;
+        is_visited[id] = true;
+        order.push(id);
+        Ok(())
+    }
+
     pub fn load_type(&self, name: &str) -> CompilationResult<Type<'ctx>> {
         match self.builtin_types.get(name) {
             Some(value_type) => Ok(value_type.clone()),
@@ -66,3 +195,31 @@ impl<'ctx> TargetBuilder<'ctx> {
         }
     }
 }
+
+#[cfg(test)]
+pub mod tests {
+    use inkwell::context::Context;
+    use lalrpop_util::ParseError;
+
+    use super::TargetBuilder;
+    use crate::errors::CompilationResult;
+    use crate::parser::grammar::ModuleParser;
+
+    /// Compiles `src` with runtime checks enabled and verifies the generated IR.
+    pub fn compile(src: &str) -> CompilationResult<()> {
+        let module_ast = ModuleParser::new()
+            .parse(src)
+            .map_err(|error| match error {
+                ParseError::User { error } => error,
+                error => panic!("{error}"),
+            })?;
+
+        let context = Context::create();
+        let target_builder = TargetBuilder::new(&context);
+        let module = target_builder.create_module("test", module_ast)?;
+        if let Err(message) = module.module_ir.verify() {
+            panic!("invalid IR: {}", message.to_string());
+        }
+        Ok(())
+    }
+}
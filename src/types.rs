@@ -1,15 +1,20 @@
 use inkwell::context::Context;
 use inkwell::types::BasicTypeEnum;
 
+use crate::array_type::ArrayType;
 use crate::errors::{CompilationError, CompilationResult};
 use crate::float_type::FloatType;
 use crate::function_type::FunctionType;
 use crate::integer_type::IntegerType;
 use crate::module_builder::ModuleBuilder;
+use crate::pointer_type::PointerType;
+use crate::struct_type::StructType;
 
 #[derive(Clone)]
 pub enum TypeSpec {
     Reference(String),
+    Array(Box<TypeSpec>, u32),
+    Pointer(Box<TypeSpec>, bool),
 }
 
 pub type BoolTypeIR<'ctx> = inkwell::types::IntType<'ctx>;
@@ -20,6 +25,10 @@ pub enum Type<'ctx> {
     Float(FloatType<'ctx>),
     Bool(BoolTypeIR<'ctx>),
     Function(FunctionType<'ctx>),
+    Struct(StructType<'ctx>),
+    Array(ArrayType<'ctx>),
+    Pointer(PointerType<'ctx>),
+    Unit,
 }
 
 impl<'ctx> Type<'ctx> {
@@ -29,6 +38,25 @@ impl<'ctx> Type<'ctx> {
     ) -> CompilationResult<Self> {
         Ok(match type_spec {
             TypeSpec::Reference(name) => module_builder.load_type(&name)?,
+            TypeSpec::Pointer(target_type, is_mutable) => {
+                let target_type = Self::from_spec(module_builder, *target_type)?;
+                PointerType::new(module_builder.context(), target_type, is_mutable).into()
+            }
+            TypeSpec::Array(element_type, len) => {
+                ArrayType::new(Self::from_spec(module_builder, *element_type)?, len)?.into()
+            }
+        })
+    }
+
+    pub fn combine_with(self, other: Self) -> CompilationResult<Self> {
+        Ok(match (self, other) {
+            (Type::Integer(lhs), Type::Integer(rhs)) => Type::Integer(lhs.combine_with(rhs)?),
+            (Type::Float(lhs), Type::Float(rhs)) => Type::Float(lhs.combine_with(rhs)?),
+            (Type::Pointer(lhs), Type::Pointer(rhs)) => Type::Pointer(lhs.combine_with(rhs)?),
+            (Type::Float(float_type), Type::Integer(_))
+            | (Type::Integer(_), Type::Float(float_type)) => Type::Float(float_type),
+            (lhs, rhs) if lhs == rhs => lhs,
+            _ => return Err(CompilationError::TypeMismatch),
         })
     }
 
@@ -62,6 +90,12 @@ impl<'ctx> Type<'ctx> {
         Type::Float(FloatType::new_f64(context))
     }
 
+    /// The type of procedures that return no value.
+    #[inline]
+    pub fn new_unit() -> Self {
+        Type::Unit
+    }
+
     #[inline]
     pub fn new_bool(context: &'ctx Context) -> Self {
         Type::Bool(context.bool_type())
@@ -76,6 +110,9 @@ impl<'ctx> TryInto<BasicTypeEnum<'ctx>> for Type<'ctx> {
             Type::Integer(int_type) => BasicTypeEnum::IntType(int_type.into()),
             Type::Float(float_type) => BasicTypeEnum::FloatType(float_type.into()),
             Type::Bool(ir) => BasicTypeEnum::IntType(ir),
+            Type::Struct(struct_type) => BasicTypeEnum::StructType(struct_type.into()),
+            Type::Array(array_type) => BasicTypeEnum::ArrayType(array_type.into()),
+            Type::Pointer(pointer_type) => BasicTypeEnum::PointerType(pointer_type.into()),
             _ => return Err(CompilationError::InvalidOperation),
         })
     }
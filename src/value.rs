@@ -1,12 +1,16 @@
 use inkwell::builder::Builder;
+use inkwell::module::Module;
 use inkwell::values::{AnyValueEnum, BasicValueEnum};
 
+use crate::array_value::ArrayValue;
 use crate::bool_value::BoolValue;
 use crate::errors::{CompilationError, CompilationResult};
 use crate::expression::{BinaryOperation, UnaryOperation};
 use crate::float_value::FloatValue;
 use crate::function::Function;
 use crate::integer_value::IntegerValue;
+use crate::pointer_value::PointerValue;
+use crate::struct_value::StructValue;
 use crate::types::Type;
 
 #[derive(Clone)]
@@ -15,6 +19,10 @@ pub enum Value<'ctx> {
     Float(FloatValue<'ctx>),
     Bool(BoolValue<'ctx>),
     Function(Function<'ctx>),
+    Struct(StructValue<'ctx>),
+    Array(ArrayValue<'ctx>),
+    Pointer(PointerValue<'ctx>),
+    Unit,
 }
 
 impl<'ctx> Value<'ctx> {
@@ -31,9 +39,32 @@ impl<'ctx> Value<'ctx> {
             Type::Function(value_type) => {
                 Function::new(value_ir.into_function_value(), value_type.clone()).into()
             }
+            Type::Struct(value_type) => {
+                StructValue::new(value_ir.into_struct_value(), value_type.clone()).into()
+            }
+            Type::Array(value_type) => {
+                ArrayValue::new(value_ir.into_array_value(), value_type.clone()).into()
+            }
+            Type::Pointer(value_type) => {
+                PointerValue::new(value_ir.into_pointer_value(), value_type.clone()).into()
+            }
+            Type::Unit => Value::Unit,
         })
     }
 
+    pub fn get_type(&self) -> Type<'ctx> {
+        match self {
+            Value::Integer(value) => Type::Integer(value.get_type()),
+            Value::Float(value) => Type::Float(value.get_type()),
+            Value::Bool(value) => Type::Bool(value.get_type()),
+            Value::Function(value) => Type::Function(value.get_type().clone()),
+            Value::Struct(value) => Type::Struct(value.get_type().clone()),
+            Value::Array(value) => Type::Array(value.get_type().clone()),
+            Value::Pointer(value) => Type::Pointer(value.get_type().clone()),
+            Value::Unit => Type::Unit,
+        }
+    }
+
     pub fn binary_operation(
         self,
         builder: &Builder<'ctx>,
@@ -44,6 +75,7 @@ impl<'ctx> Value<'ctx> {
             Value::Integer(value) => value.binary_operation(builder, op, other),
             Value::Float(value) => value.binary_operation(builder, op, other),
             Value::Bool(value) => value.binary_operation(builder, op, other),
+            Value::Pointer(value) => value.binary_operation(builder, op, other),
             _ => Err(CompilationError::InvalidOperation),
         }
     }
@@ -57,7 +89,32 @@ impl<'ctx> Value<'ctx> {
             Value::Integer(value) => value.unary_operation(builder, op),
             Value::Float(value) => value.unary_operation(builder, op),
             Value::Bool(value) => value.unary_operation(builder, op),
-            _ => Err(CompilationError::InvalidOperation),
+            Value::Function(_) => Err(Self::unsupported_unary_operation(op, "function")),
+            Value::Struct(_) => Err(Self::unsupported_unary_operation(op, "struct")),
+            Value::Array(_) => Err(Self::unsupported_unary_operation(op, "array")),
+            Value::Pointer(_) => Err(Self::unsupported_unary_operation(op, "pointer")),
+            Value::Unit => Err(Self::unsupported_unary_operation(op, "unit")),
+        }
+    }
+
+    fn unsupported_unary_operation(op: UnaryOperation, kind: &str) -> CompilationError {
+        match op {
+            UnaryOperation::LogicalNot => CompilationError::InvalidLogicalNot(kind.to_string()),
+            _ => CompilationError::InvalidOperation,
+        }
+    }
+
+    pub fn cast(
+        self,
+        builder: &Builder<'ctx>,
+        module_ir: &Module<'ctx>,
+        target_type: &Type<'ctx>,
+    ) -> CompilationResult<Self> {
+        match self {
+            Value::Integer(value) => value.cast(builder, target_type),
+            Value::Float(value) => value.cast(builder, module_ir, target_type),
+            Value::Bool(value) => value.cast(builder, target_type),
+            _ => Err(CompilationError::InvalidCast),
         }
     }
 
@@ -75,6 +132,13 @@ impl<'ctx> Value<'ctx> {
                 _ => return Err(CompilationError::TypeMismatch),
             },
             Type::Function(required_type) => required_type.validate_value(self)?.into(),
+            Type::Struct(required_type) => required_type.validate_value(self)?.into(),
+            Type::Array(required_type) => required_type.validate_value(self)?.into(),
+            Type::Pointer(required_type) => required_type.validate_value(self)?.into(),
+            Type::Unit => match self {
+                Value::Unit => Value::Unit,
+                _ => return Err(CompilationError::TypeMismatch),
+            },
         })
     }
 }
@@ -87,6 +151,9 @@ impl<'ctx> TryInto<BasicValueEnum<'ctx>> for Value<'ctx> {
             Value::Integer(value) => BasicValueEnum::IntValue(value.into()),
             Value::Bool(value) => BasicValueEnum::IntValue(value.into()),
             Value::Float(value) => BasicValueEnum::FloatValue(value.into()),
+            Value::Struct(value) => BasicValueEnum::StructValue(value.into()),
+            Value::Array(value) => BasicValueEnum::ArrayValue(value.into()),
+            Value::Pointer(value) => BasicValueEnum::PointerValue(value.into()),
             _ => return Err(CompilationError::InvalidOperation),
         })
     }
@@ -0,0 +1,91 @@
+use inkwell::builder::Builder;
+use inkwell::types::BasicTypeEnum;
+use inkwell::values::{AnyValue, BasicValueEnum, IntValue};
+
+use crate::errors::{CompilationError, CompilationResult};
+use crate::types::Type;
+use crate::value::Value;
+
+type VariableIR<'ctx> = inkwell::values::PointerValue<'ctx>;
+
+#[derive(Clone)]
+pub struct Variable<'ctx> {
+    ir: VariableIR<'ctx>,
+    value_type: Type<'ctx>,
+    is_mutable: bool,
+}
+
+impl<'ctx> Variable<'ctx> {
+    pub fn new(ir: VariableIR<'ctx>, value_type: Type<'ctx>, is_mutable: bool) -> Self {
+        Self {
+            ir,
+            value_type,
+            is_mutable,
+        }
+    }
+
+    #[inline(always)]
+    pub fn get_type(&self) -> &Type<'ctx> {
+        &self.value_type
+    }
+
+    #[inline(always)]
+    pub fn ir(&self) -> &VariableIR<'ctx> {
+        &self.ir
+    }
+
+    #[inline(always)]
+    pub fn is_mutable(&self) -> bool {
+        self.is_mutable
+    }
+
+    pub fn get_field(&self, builder: &Builder<'ctx>, name: &str) -> CompilationResult<Self> {
+        let struct_type = match &self.value_type {
+            Type::Struct(struct_type) => struct_type,
+            _ => return Err(CompilationError::InvalidOperation),
+        };
+
+        let (field_id, field_type) = struct_type.get_field(name)?;
+        let field_ir = builder.build_struct_gep(struct_type.ir().clone(), self.ir, field_id, "")?;
+        Ok(Variable::new(field_ir, field_type, self.is_mutable))
+    }
+
+    /// The index is not checked, callers are expected to validate it against the array length.
+    pub fn get_element(
+        &self,
+        builder: &Builder<'ctx>,
+        index_ir: IntValue<'ctx>,
+    ) -> CompilationResult<Self> {
+        let array_type = match &self.value_type {
+            Type::Array(array_type) => array_type,
+            _ => return Err(CompilationError::InvalidOperation),
+        };
+
+        let zero_ir = index_ir.get_type().const_zero();
+        let element_ir = unsafe {
+            builder.build_in_bounds_gep(
+                array_type.ir().clone(),
+                self.ir,
+                &[zero_ir, index_ir],
+                "",
+            )?
+        };
+        Ok(Variable::new(
+            element_ir,
+            array_type.element_type().clone(),
+            self.is_mutable,
+        ))
+    }
+
+    pub fn load(&self, builder: &Builder<'ctx>) -> CompilationResult<Value<'ctx>> {
+        let value_type_ir: BasicTypeEnum = self.value_type.clone().try_into()?;
+        let value_ir = builder.build_load(value_type_ir, self.ir, "")?;
+        Value::from_ir(value_ir.as_any_value_enum(), &self.value_type)
+    }
+
+    pub fn store(&self, builder: &Builder<'ctx>, value: Value<'ctx>) -> CompilationResult<()> {
+        let value_ir: BasicValueEnum = value.try_into()?;
+        builder.build_store(self.ir, value_ir)?;
+        Ok(())
+    }
+}